@@ -0,0 +1,176 @@
+use regex::Regex;
+use serde_json::Value;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::data::Data;
+use crate::error::{argument_path, Error, ROOT_PATH};
+use crate::evaluator::Settings;
+use crate::expression::Expression;
+use crate::limits::limit_exceeded;
+use crate::log_sink::{LogSink, StdoutSink};
+use crate::operators::Operator;
+use crate::trace::Tracer;
+
+/// The state of a single evaluation of a rule. It is shared by the `Data` the evaluation was
+/// started with and all data that is derived from it with `Data::scoped`.
+pub(crate) struct Context<'a> {
+    pub settings: &'a Settings,
+    /// The compiled literal patterns of the `match` operations in the rule.
+    pub regexes: &'a HashMap<String, Regex>,
+    /// Records the evaluated operations if the evaluation is traced.
+    pub tracer: Option<Tracer>,
+    /// The first error that occurred. Once set, the remaining expressions are not computed.
+    error: RefCell<Option<Error>>,
+    /// The number of operations evaluated so far.
+    steps: Cell<usize>,
+    /// The operations whose evaluation is in progress, starting with the outermost.
+    operations: RefCell<Vec<Operation>>,
+}
+
+/// An operation whose evaluation is in progress.
+struct Operation {
+    name: Name,
+    /// The index of the operation in the arguments of the enclosing operation, `None` if the
+    /// enclosing operator did not compute it as an argument.
+    argument: Option<usize>,
+}
+
+/// The name of an operator, shared with the registry for custom operators.
+enum Name {
+    Builtin(&'static str),
+    Custom(Arc<str>),
+}
+
+impl Name {
+    fn as_str(&self) -> &str {
+        match self {
+            Name::Builtin(name) => name,
+            Name::Custom(name) => name,
+        }
+    }
+}
+
+/// Ends the evaluation of the operation entered with `Context::enter` when it is dropped.
+pub(crate) struct Entered<'c> {
+    operations: &'c RefCell<Vec<Operation>>,
+}
+
+impl Drop for Entered<'_> {
+    fn drop(&mut self) {
+        self.operations.borrow_mut().pop();
+    }
+}
+
+impl<'a> Context<'a> {
+    pub fn new(settings: &'a Settings, regexes: &'a HashMap<String, Regex>) -> Context<'a> {
+        Context {
+            settings,
+            regexes,
+            tracer: None,
+            error: RefCell::new(None),
+            steps: Cell::new(0),
+            operations: RefCell::new(vec![]),
+        }
+    }
+
+    /// Starts the evaluation of the given expression with the given data, until the returned guard
+    /// is dropped. Returns `None` for constants, which are no operations.
+    pub fn enter(&self, expr: &Expression, data: &Data) -> Option<Entered<'_>> {
+        let name = match expr {
+            Expression::Constant(_) => return None,
+            Expression::Computed(operator, _) => Name::Builtin(operator.as_str()),
+            Expression::Custom(operator, _) => Name::Custom(operator.shared_name()),
+        };
+        self.operations.borrow_mut().push(Operation {
+            name,
+            argument: data.argument_index(),
+        });
+        Some(Entered {
+            operations: &self.operations,
+        })
+    }
+
+    /// Returns the JSONPath of the innermost operation in progress. An operation that was not
+    /// computed as an argument has the path of the enclosing operation.
+    pub fn path(&self) -> String {
+        let operations = self.operations.borrow();
+        let mut path = String::from(ROOT_PATH);
+        for pair in operations.windows(2) {
+            if let Some(index) = pair[1].argument {
+                path = argument_path(&path, pair[0].name.as_str(), index);
+            }
+        }
+        path
+    }
+
+    /// Aborts the evaluation with the given error, unless it was aborted before.
+    pub fn fail(&self, error: Error) {
+        let mut current = self.error.borrow_mut();
+        if current.is_none() {
+            *current = Some(error);
+        }
+    }
+
+    /// Counts the evaluation of the given expression towards the step limit. Aborts the evaluation
+    /// and returns `false` if the limit is exceeded. The expression has to be entered before.
+    pub fn step(&self, expr: &Expression) -> bool {
+        if let Expression::Constant(_) = expr {
+            return true;
+        }
+
+        let steps = self.steps.get() + 1;
+        self.steps.set(steps);
+        match self.settings.limits.max_steps {
+            Some(max_steps) if steps > max_steps => {
+                self.fail(limit_exceeded("max_steps", &self.path()));
+                false
+            }
+            _ => true,
+        }
+    }
+
+    /// Aborts the evaluation if the value computed by the given expression, which is still entered,
+    /// exceeds the size limit. Values that the expression only passes on are data or were checked
+    /// where they were computed.
+    pub fn check_size(&self, expr: &Expression, value: &Value) {
+        let passes_on = match expr {
+            Expression::Constant(_) => true,
+            Expression::Computed(operator, _) => matches!(
+                operator,
+                Operator::Variable
+                    | Operator::If
+                    | Operator::And
+                    | Operator::Or
+                    | Operator::Let
+                    | Operator::Log
+            ),
+            Expression::Custom(..) => false,
+        };
+        if !passes_on && self.settings.limits.exceeds_size(value) {
+            self.fail(limit_exceeded("max_size", &self.path()));
+        }
+    }
+
+    pub fn has_failed(&self) -> bool {
+        self.error.borrow().is_some()
+    }
+
+    /// Returns the given result of the evaluation, or the error if it failed.
+    pub fn finish<T>(self, result: T) -> Result<T, Error> {
+        match self.error.into_inner() {
+            Some(error) => Err(error),
+            None => Ok(result),
+        }
+    }
+}
+
+/// Passes the value logged by the current operation to the configured sink, or prints it if the
+/// data has no context.
+pub(crate) fn log(context: Option<&Context>, value: &Value) {
+    match context {
+        Some(context) => context.settings.log_sink.log(value, &context.path()),
+        None => StdoutSink.log(value, ROOT_PATH),
+    }
+}
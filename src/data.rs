@@ -1,24 +1,189 @@
 use serde_json::{Number, Value};
+use std::borrow::Cow;
 use std::convert::TryFrom;
 
-/// Contains a JSON value that is passed as data for the evaluation JsonLogic expression.
-pub struct Data<'a>(&'a Value);
+use crate::context::Context;
+use crate::data_source::DataSource;
+
+/// Contains the data that is passed for the evaluation JsonLogic expression, either a JSON value or
+/// any other `DataSource`.
+///
+/// Besides the data, it carries the state of the current evaluation, e.g. the trace that is
+/// recorded by `apply_with_trace`. Operators that evaluate their arguments against other values,
+/// like `map` does for each array element, therefore create the data for those values with
+/// `scoped`.
+///
+/// Scoped data keeps the data it was created from as its parent. Paths starting with `../` are
+/// resolved in the parent, paths starting with `$root.` in the data the evaluation was started
+/// with, and `$index` is the index of the current array element. The names bound by `let`
+/// operations are visible in all scopes nested in their body.
+pub struct Data<'a> {
+    source: Source<'a>,
+    context: Option<&'a Context<'a>>,
+    /// The data of the enclosing scope, or the data of the `let` operation for its body.
+    parent: Option<&'a Data<'a>>,
+    /// The index of the array element the data was scoped to.
+    index: Option<usize>,
+    /// The names bound by the `let` operation if this is the data of its body.
+    bindings: Option<&'a Bindings<'a>>,
+    /// The index of the argument of the current operation that is computed with this data, to
+    /// report the path of the argument in errors, logs and traces.
+    argument: Option<usize>,
+}
+
+/// The names bound by a `let` operation and their values. A value is `None` if it is not known in
+/// a partial evaluation.
+pub(crate) type Bindings<'a> = [(&'a str, Option<Value>)];
+
+/// The prefix of a path that refers to the parent scope.
+pub(crate) const PARENT: &str = "../";
+/// The path of the outermost scope, and the prefix of paths in it.
+pub(crate) const ROOT: &str = "$root";
+/// The path of the index of the current array element.
+const INDEX: &str = "$index";
+
+#[derive(Clone, Copy)]
+enum Source<'a> {
+    Json(&'a Value),
+    Custom(&'a dyn DataSource),
+}
+
+impl<'a> Source<'a> {
+    fn new(source: &'a dyn DataSource) -> Source<'a> {
+        match source.as_json() {
+            Some(value) => Source::Json(value),
+            None => Source::Custom(source),
+        }
+    }
+}
 
 impl<'a> Data<'a> {
     /// Creates a new struct from the given json value.
-    pub fn from_json(data: &Value) -> Data {
-        Data(data)
+    pub fn from_json(data: &Value) -> Data<'_> {
+        Data {
+            source: Source::Json(data),
+            context: None,
+            parent: None,
+            index: None,
+            bindings: None,
+            argument: None,
+        }
+    }
+
+    /// Creates a new struct that resolves variables through the given source.
+    pub fn from_source(source: &'a dyn DataSource) -> Data<'a> {
+        Data {
+            source: Source::new(source),
+            context: None,
+            parent: None,
+            index: None,
+            bindings: None,
+            argument: None,
+        }
+    }
+
+    /// Creates the data for an evaluation with the given context.
+    pub(crate) fn with_context(source: &'a dyn DataSource, context: &'a Context<'a>) -> Data<'a> {
+        Data {
+            source: Source::new(source),
+            context: Some(context),
+            parent: None,
+            index: None,
+            bindings: None,
+            argument: None,
+        }
     }
 
     /// Creates an empty struct, encapsulating a null value.
     #[allow(dead_code)]
     pub fn empty() -> Data<'static> {
-        Data(&Value::Null)
+        Data {
+            source: Source::Json(&Value::Null),
+            context: None,
+            parent: None,
+            index: None,
+            bindings: None,
+            argument: None,
+        }
+    }
+
+    /// Creates the data to evaluate an expression against the given value, as part of the same
+    /// evaluation as this data.
+    pub fn scoped<'b>(&'b self, value: &'b Value) -> Data<'b> {
+        Data {
+            source: Source::Json(value),
+            context: self.context,
+            parent: Some(self),
+            index: None,
+            bindings: None,
+            argument: self.argument,
+        }
+    }
+
+    /// Like `scoped`, for the element at the given index of an array.
+    pub fn scoped_element<'b>(&'b self, value: &'b Value, index: usize) -> Data<'b> {
+        Data {
+            index: Some(index),
+            ..self.scoped(value)
+        }
+    }
+
+    /// Creates the data to evaluate the body of a `let` operation, in which `var` resolves the
+    /// bound names to their values. Otherwise it is the same as this data.
+    pub(crate) fn bind<'b>(&'b self, bindings: &'b Bindings<'b>) -> Data<'b> {
+        Data {
+            source: self.source,
+            context: self.context,
+            parent: Some(self),
+            index: self.index,
+            bindings: Some(bindings),
+            argument: self.argument,
+        }
+    }
+
+    /// Returns the data to compute the argument at the given index of the current operation with.
+    /// Operators compute each argument with it, so that errors, logs and traces report the path
+    /// of the argument in the rule.
+    ///
+    /// The scoped data for an argument is created from the data returned by this method, e.g. the
+    /// logic of `map` is computed with `data.argument(1).scoped_element(element, index)`.
+    pub fn argument(&self, index: usize) -> Data<'a> {
+        Data {
+            source: self.source,
+            context: self.context,
+            parent: self.parent,
+            index: self.index,
+            bindings: self.bindings,
+            argument: Some(index),
+        }
+    }
+
+    /// The index of the argument of the current operation that is computed with this data, `None`
+    /// if the data was not created with `argument`.
+    pub(crate) fn argument_index(&self) -> Option<usize> {
+        self.argument
+    }
+
+    /// Returns the data for the arguments of an operation computed with this data, which are not
+    /// known to be arguments until the operator passes their index to `argument`.
+    pub(crate) fn operation(&self) -> Data<'a> {
+        Data {
+            argument: None,
+            ..self.argument(0)
+        }
+    }
+
+    /// Gets the plain json data that is encapsulated by this struct. For a `DataSource` this is
+    /// `DataSource::value`.
+    pub fn get_plain(&self) -> Cow<'a, Value> {
+        match self.source {
+            Source::Json(value) => Cow::Borrowed(value),
+            Source::Custom(source) => source.value(),
+        }
     }
 
-    /// Gets the plain json data that is encapsulated by this struct.
-    pub fn get_plain(&self) -> &Value {
-        self.0
+    pub(crate) fn context(&self) -> Option<&'a Context<'a>> {
+        self.context
     }
 
     /// Tries to get part of the encapsulate data by the given path.
@@ -27,20 +192,221 @@ impl<'a> Data<'a> {
     /// The string may contain multiple steps (separated by a dot) to acccess nested values inside
     /// objects, arrays or even characters in a string.
     ///
-    /// TODO: Would it be possible to clone only if necessary?
-    pub fn get_value(&self, path: &Value) -> Option<Value> {
+    /// A string path may refer to an enclosing scope, see `Data`. A `../` or `$root` path without
+    /// further steps returns the whole data of that scope.
+    ///
+    /// A JSON value is borrowed from the encapsulated data, only a character of a string is owned.
+    pub fn get_value(&self, path: &Value) -> Option<Cow<'a, Value>> {
         match path {
-            Value::String(path) => self.by_string(path),
-            Value::Number(number) => self.by_number(number),
+            Value::String(path) => {
+                let (scope, path, prefixed) = self.scope(path)?;
+                match (path, scope.index) {
+                    (INDEX, Some(index)) => Some(Cow::Owned(Value::from(index))),
+                    ("", _) if prefixed => Some(scope.get_plain()),
+                    _ if prefixed => scope.by_string(path),
+                    _ => scope
+                        .lookup(path, Data::by_string, |value, rest| match rest {
+                            Some(rest) => resolve(value, rest).map(Found::into_value),
+                            None => Some(Cow::Borrowed(value)),
+                        })
+                        .found(),
+                }
+            }
+            Value::Number(number) => match self.source {
+                Source::Json(value) => Found::by_number(value, number).map(Found::into_value),
+                Source::Custom(source) => source.get(&number.to_string()),
+            },
             _ => None,
         }
     }
 
+    /// Returns whether there is a value at the given path, see `get_value`. Unlike
+    /// `get_value(path).is_some()`, this never allocates for JSON values.
+    pub fn has_value(&self, path: &Value) -> bool {
+        match path {
+            Value::String(path) => match self.scope(path) {
+                Some((scope, path, prefixed)) => match (path, scope.index) {
+                    (INDEX, Some(_)) => true,
+                    ("", _) if prefixed => true,
+                    _ if prefixed => scope.contains(path),
+                    _ => scope
+                        .lookup(path, Data::find, |value, rest| match rest {
+                            Some(rest) => resolve(value, rest).map(drop),
+                            None => Some(()),
+                        })
+                        .found()
+                        .is_some(),
+                },
+                None => false,
+            },
+            Value::Number(number) => match self.source {
+                Source::Json(value) => Found::by_number(value, number).is_some(),
+                Source::Custom(source) => source.contains(&number.to_string()),
+            },
+            _ => false,
+        }
+    }
+
+    /// Returns whether the path refers to a name bound by a `let` operation whose value is not
+    /// known in a partial evaluation.
+    pub(crate) fn is_unknown(&self, path: &Value) -> bool {
+        match path {
+            Value::String(path) => matches!(
+                self.scope(path),
+                Some((scope, path, false))
+                    if matches!(scope.lookup(path, Data::find, |_, _| Some(())), Lookup::Unknown)
+            ),
+            _ => false,
+        }
+    }
+
+    fn by_string(&self, path: &str) -> Option<Cow<'a, Value>> {
+        match self.source {
+            Source::Json(value) => Found::by_string(value, path).map(Found::into_value),
+            Source::Custom(source) => source.get(path),
+        }
+    }
+
+    fn contains(&self, path: &str) -> bool {
+        match self.source {
+            Source::Json(value) => Found::by_string(value, path).is_some(),
+            Source::Custom(source) => source.contains(path),
+        }
+    }
+
+    fn find(&self, path: &str) -> Option<()> {
+        if self.contains(path) {
+            Some(())
+        } else {
+            None
+        }
+    }
+
+    /// Looks up a path without a scope prefix. A name bound by a `let` operation takes precedence
+    /// over the data of the scope the operation is evaluated in, but not over the data of an array
+    /// element in its body. The value of a bound name is resolved with `in_binding`, which is
+    /// passed the remaining steps of the path, and the data with `in_source`.
+    fn lookup<T>(
+        &self,
+        path: &str,
+        in_source: impl Fn(&Data<'a>, &str) -> Option<T>,
+        in_binding: impl Fn(&'a Value, Option<&str>) -> Option<T>,
+    ) -> Lookup<T> {
+        let (name, rest) = match path.find('.') {
+            Some(index) => (&path[..index], Some(&path[index + 1..])),
+            None => (path, None),
+        };
+
+        let mut searched_source = false;
+        let mut data = Some(self);
+        while let Some(current) = data {
+            match current.bindings {
+                Some(bindings) => {
+                    if let Some((_, value)) =
+                        bindings.iter().rev().find(|(bound, _)| *bound == name)
+                    {
+                        return match value {
+                            Some(value) => {
+                                in_binding(value, rest).map_or(Lookup::Missing, Lookup::Found)
+                            }
+                            None => Lookup::Unknown,
+                        };
+                    }
+                }
+                // Only the data of the innermost scope is searched, outer scopes have to be
+                // referenced with a prefix.
+                None if !searched_source => {
+                    if let Some(value) = in_source(current, path) {
+                        return Lookup::Found(value);
+                    }
+                    searched_source = true;
+                }
+                None => (),
+            }
+            data = current.parent;
+        }
+        Lookup::Missing
+    }
+
+    /// Returns the scope the path refers to, the remaining path in that scope, and whether the
+    /// path had a prefix that referred to a scope. Returns `None` if the path refers to a parent
+    /// of the outermost scope.
+    fn scope<'s, 'p>(&'s self, path: &'p str) -> Option<(&'s Data<'a>, &'p str, bool)> {
+        if let Some(rest) = path.strip_prefix(ROOT) {
+            if rest.is_empty() || rest.starts_with('.') {
+                let mut root = self;
+                while let Some(parent) = root.parent {
+                    root = parent;
+                }
+                return Some((root, rest.get(1..).unwrap_or(""), true));
+            }
+        }
+
+        let mut scope = self;
+        let mut rest = path;
+        while let Some(stripped) = rest.strip_prefix(PARENT) {
+            scope = scope.parent_scope()?;
+            rest = stripped;
+        }
+        if rest == ".." {
+            return Some((scope.parent_scope()?, "", true));
+        }
+        Some((scope, rest, rest.len() < path.len()))
+    }
+
+    /// Returns the data of the enclosing scope. The data of a `let` body is part of the scope the
+    /// operation is evaluated in.
+    fn parent_scope(&self) -> Option<&Data<'a>> {
+        let mut data = self;
+        while data.bindings.is_some() {
+            data = data.parent?;
+        }
+        data.parent
+    }
+}
+
+/// The result of looking up a path in the data.
+enum Lookup<T> {
+    Found(T),
+    /// The path refers to a name bound by a `let` operation whose value is not known.
+    Unknown,
+    Missing,
+}
+
+impl<T> Lookup<T> {
+    fn found(self) -> Option<T> {
+        match self {
+            Lookup::Found(value) => Some(value),
+            Lookup::Unknown | Lookup::Missing => None,
+        }
+    }
+}
+
+/// Resolves the path of dot-separated steps in the given value, see `Data::get_value`.
+pub(crate) fn resolve<'a>(value: &'a Value, path: &str) -> Option<Found<'a>> {
+    Found::by_string(value, path)
+}
+
+/// A value found in the data by a path.
+pub(crate) enum Found<'a> {
+    Value(&'a Value),
+    /// A character of a string, which is no value of the data itself.
+    Char(char),
+}
+
+impl<'a> Found<'a> {
+    pub fn into_value(self) -> Cow<'a, Value> {
+        match self {
+            Found::Value(value) => Cow::Borrowed(value),
+            Found::Char(ch) => Cow::Owned(Value::String(ch.to_string())),
+        }
+    }
+
     /// Trys to get a value from the given data by the path. This can be a simple key or a
     /// stringified index for strings and arrays but complex dot-notation access paths are also
     /// supported.
-    fn by_string(&self, path: &str) -> Option<Value> {
-        let mut data_part = self.0;
+    fn by_string(value: &'a Value, path: &str) -> Option<Found<'a>> {
+        let mut data_part = value;
 
         // While we can traverse through arrays and objects, we can't for a characters. Character
         // access in a string must therefore be the last step in the given path. To handle that
@@ -92,30 +458,64 @@ impl<'a> Data<'a> {
 
         // If in the last step a character from a string was accessed, return it.
         if let Some(ch) = prev_step_char {
-            return Some(Value::String(ch.to_string()));
+            return Some(Found::Char(ch));
         }
 
-        // TODO: Could we avoid cloning?
-        Some(data_part.clone())
+        Some(Found::Value(data_part))
     }
 
     /// Extracts a value from the given data by index. Data can either be an array, a string or an
     /// object containing the stringified index as a key. Otherwise returns `None`.
-    fn by_number(&self, num: &Number) -> Option<Value> {
-        match self.0 {
+    fn by_number(value: &'a Value, num: &Number) -> Option<Found<'a>> {
+        match value {
             Value::Array(arr) => num
                 .as_u64()
                 .and_then(|index| usize::try_from(index).ok())
-                .and_then(|index| arr.get(index).cloned()),
-            Value::Object(obj) => obj.get(&num.to_string()).cloned(),
+                .and_then(|index| arr.get(index))
+                .map(Found::Value),
+            Value::Object(obj) => obj.get(&num.to_string()).map(Found::Value),
             Value::String(s) => num
                 .as_u64()
                 .and_then(|index| usize::try_from(index).ok())
                 .and_then(|index| s.chars().nth(index))
-                .map(|ch| Value::String(ch.to_string())),
+                .map(Found::Char),
             _ => None,
         }
     }
 }
 
 // TODO: Move tests from variable operator to this file.
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn get_value_borrows() {
+        let data_json = json!({ "a": { "b": [1, 2] }, "s": "abc" });
+        let data = Data::from_json(&data_json);
+
+        match data.get_value(&json!("a.b")) {
+            Some(Cow::Borrowed(value)) => assert!(std::ptr::eq(value, &data_json["a"]["b"])),
+            other => panic!("expected a borrowed value, got {:?}", other),
+        }
+        assert_eq!(data.get_value(&json!("s.1")), Some(Cow::Owned(json!("b"))));
+        assert_eq!(data.get_value(&json!("a.c")), None);
+    }
+
+    #[test]
+    fn has_value() {
+        let data_json = json!({ "a": { "b": [1, null] }, "s": "abc", "1": true });
+        let data = Data::from_json(&data_json);
+
+        assert!(data.has_value(&json!("a")));
+        assert!(data.has_value(&json!("a.b.1")));
+        assert!(data.has_value(&json!("s.2")));
+        assert!(data.has_value(&json!(1)));
+        assert!(!data.has_value(&json!("a.b.2")));
+        assert!(!data.has_value(&json!("s.3")));
+        assert!(!data.has_value(&json!("s.0.0")));
+        assert!(!data.has_value(&json!(null)));
+    }
+}
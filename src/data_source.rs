@@ -0,0 +1,226 @@
+use serde_json::{Map, Value};
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use crate::data;
+
+/// Provides the data a rule is evaluated against, so it does not have to be converted into a
+/// single `serde_json::Value` first. `var`, `missing` and `missing_some` resolve their paths
+/// through it.
+///
+/// Paths are the strings used by `var`, e.g. `"user.name"` or `"items.0"`. A number like in
+/// `{ "var": 1 }` is passed as its string representation.
+///
+/// Besides `Value`, the trait is implemented for `HashMap<String, Value>`, for closures taking the
+/// path and returning `Option<Value>`, and for `Layered` sources:
+///
+/// ```
+/// use jsonlogic::data_source::Layered;
+/// use jsonlogic::CompiledRule;
+/// use serde_json::{json, Value};
+///
+/// let rule = CompiledRule::new(&json!({ "*": [{ "var": "amount" }, { "var": "fee" }] })).unwrap();
+///
+/// let request = |path: &str| match path {
+///     "amount" => Some(json!(100)),
+///     _ => None,
+/// };
+/// let defaults = json!({ "fee": 0.05, "amount": 0 });
+///
+/// let data = Layered::new(vec![&request, &defaults]);
+/// assert_eq!(rule.evaluate(&data), Ok(json!(5.0)));
+/// ```
+pub trait DataSource {
+    /// Returns the value at the given path, or `None` if there is none.
+    fn get(&self, path: &str) -> Option<Cow<'_, Value>>;
+
+    /// Returns whether there is a value at the given path. Sources that can answer this without
+    /// producing the value should override it, it is used by `missing` and `missing_some`.
+    fn contains(&self, path: &str) -> bool {
+        self.get(path).is_some()
+    }
+
+    /// Returns the whole data, which is what `{ "var": "" }` evaluates to. Sources that cannot
+    /// list their values return `Value::Null`, which is the default.
+    fn value(&self) -> Cow<'_, Value> {
+        Cow::Owned(Value::Null)
+    }
+
+    /// Returns the source as a JSON value if it is one, which lets the evaluation access it
+    /// directly instead of through `get`.
+    fn as_json(&self) -> Option<&Value> {
+        None
+    }
+}
+
+impl DataSource for Value {
+    fn get(&self, path: &str) -> Option<Cow<'_, Value>> {
+        data::resolve(self, path).map(data::Found::into_value)
+    }
+
+    fn contains(&self, path: &str) -> bool {
+        data::resolve(self, path).is_some()
+    }
+
+    fn value(&self) -> Cow<'_, Value> {
+        Cow::Borrowed(self)
+    }
+
+    fn as_json(&self) -> Option<&Value> {
+        Some(self)
+    }
+}
+
+/// The first step of a path is the key, the remaining steps are resolved within its value.
+impl DataSource for HashMap<String, Value> {
+    fn get(&self, path: &str) -> Option<Cow<'_, Value>> {
+        resolve_key(self, path).map(data::Found::into_value)
+    }
+
+    fn contains(&self, path: &str) -> bool {
+        resolve_key(self, path).is_some()
+    }
+
+    fn value(&self) -> Cow<'_, Value> {
+        let map = self
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect::<Map<_, _>>();
+        Cow::Owned(Value::Object(map))
+    }
+}
+
+fn resolve_key<'a>(map: &'a HashMap<String, Value>, path: &str) -> Option<data::Found<'a>> {
+    let (key, rest) = match path.find('.') {
+        Some(index) => (&path[..index], Some(&path[index + 1..])),
+        None => (path, None),
+    };
+    let value = map.get(key)?;
+    match rest {
+        Some(rest) => data::resolve(value, rest),
+        None => Some(data::Found::Value(value)),
+    }
+}
+
+impl<F> DataSource for F
+where
+    F: Fn(&str) -> Option<Value>,
+{
+    fn get(&self, path: &str) -> Option<Cow<'_, Value>> {
+        self(path).map(Cow::Owned)
+    }
+}
+
+/// Fetches the value of a path only when the evaluation reaches a `var`, `missing` or
+/// `missing_some` that refers to it, e.g. from a database. Since `and`, `or` and `if`
+/// short-circuit, only the values that are needed for the result are fetched. Each path is fetched
+/// at most once, the value is cached for later accesses.
+///
+/// Create a new source for each evaluation, so values are not reused across evaluations, and check
+/// which paths were fetched afterwards:
+///
+/// ```
+/// use jsonlogic::data_source::LazySource;
+/// use serde_json::json;
+///
+/// let data = LazySource::new(|path: &str| match path {
+///     "vip" => Some(json!(true)),
+///     "orders" => Some(json!(12)),
+///     _ => None,
+/// });
+///
+/// let rule = json!({ "or": [{ "var": "vip" }, { ">": [{ "var": "orders" }, 10] }] });
+/// assert_eq!(jsonlogic::apply(&rule, &data), Ok(json!(true)));
+/// assert_eq!(data.fetched_paths(), vec!["vip"]);
+/// ```
+pub struct LazySource<F> {
+    fetch: F,
+    /// The fetched values by path, `None` if the path has no value.
+    cache: RefCell<HashMap<String, Option<Value>>>,
+    /// The fetched paths in the order they were fetched.
+    fetched: RefCell<Vec<String>>,
+}
+
+impl<F> LazySource<F>
+where
+    F: Fn(&str) -> Option<Value>,
+{
+    /// Creates a source that fetches the value of a path with the given function.
+    pub fn new(fetch: F) -> LazySource<F> {
+        LazySource {
+            fetch,
+            cache: RefCell::new(HashMap::new()),
+            fetched: RefCell::new(vec![]),
+        }
+    }
+
+    /// Returns the paths that were fetched so far in the order they were fetched, including those
+    /// without a value.
+    pub fn fetched_paths(&self) -> Vec<String> {
+        self.fetched.borrow().clone()
+    }
+
+    /// Returns the cached value of the path, fetching it on the first access.
+    fn resolve(&self, path: &str) -> Option<Value> {
+        if let Some(value) = self.cache.borrow().get(path) {
+            return value.clone();
+        }
+
+        let value = (self.fetch)(path);
+        self.cache
+            .borrow_mut()
+            .insert(path.to_owned(), value.clone());
+        self.fetched.borrow_mut().push(path.to_owned());
+        value
+    }
+}
+
+impl<F> DataSource for LazySource<F>
+where
+    F: Fn(&str) -> Option<Value>,
+{
+    fn get(&self, path: &str) -> Option<Cow<'_, Value>> {
+        self.resolve(path).map(Cow::Owned)
+    }
+
+    fn contains(&self, path: &str) -> bool {
+        let cached = self.cache.borrow().get(path).map(Option::is_some);
+        cached.unwrap_or_else(|| self.resolve(path).is_some())
+    }
+}
+
+/// Resolves paths in several sources, e.g. the request over the merchant configuration over the
+/// defaults. A path is resolved in the first source that has a value for it.
+pub struct Layered<'a> {
+    layers: Vec<&'a dyn DataSource>,
+}
+
+impl<'a> Layered<'a> {
+    /// Creates a source of the given layers, the first one takes precedence.
+    pub fn new(layers: Vec<&'a dyn DataSource>) -> Layered<'a> {
+        Layered { layers }
+    }
+}
+
+impl DataSource for Layered<'_> {
+    fn get(&self, path: &str) -> Option<Cow<'_, Value>> {
+        self.layers.iter().find_map(|layer| layer.get(path))
+    }
+
+    fn contains(&self, path: &str) -> bool {
+        self.layers.iter().any(|layer| layer.contains(path))
+    }
+
+    /// Merges the objects of the layers, the first layer takes precedence. Layers whose value is
+    /// no object are skipped.
+    fn value(&self) -> Cow<'_, Value> {
+        let mut merged = Map::new();
+        for layer in self.layers.iter().rev() {
+            if let Value::Object(map) = layer.value().into_owned() {
+                merged.extend(map);
+            }
+        }
+        Cow::Owned(Value::Object(merged))
+    }
+}
@@ -0,0 +1,159 @@
+use std::error;
+use std::fmt;
+
+/// An error returned when parsing, analyzing or applying a JsonLogic rule.
+///
+/// Errors that belong to a node of the rule carry its location as a JSONPath, e.g. `$.and[1]` for
+/// the second argument of the `and` operation at the root of the rule. When a
+/// rule is parsed, arguments given without the surrounding array (`{ "var": "a" }`) are addressed
+/// by the operator key alone. Errors found in an already parsed expression always address
+/// arguments by their index.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The rule uses an operator that is neither built-in nor registered.
+    UnknownOperator { name: String, path: String },
+    /// An operator was called with arguments it cannot handle.
+    InvalidArguments {
+        operator: String,
+        message: String,
+        path: String,
+    },
+    /// A `var` operation whose argument has to be computed, so the variable name is not known
+    /// before the rule is applied.
+    NonStaticVariable { path: String },
+    /// A `match` operation with a pattern or flags that cannot be compiled into a regex.
+    InvalidRegex {
+        pattern: String,
+        message: String,
+        path: String,
+    },
+    /// An arithmetic operation in integer mode whose result does not fit into an `i64` or `u64`.
+    IntegerOverflow { operator: String, path: String },
+    /// Applying the rule exceeded one of the configured limits.
+    LimitExceeded { limit: String, path: String },
+    /// A custom operator was registered under the name of a built-in operator.
+    ReservedOperator { name: String },
+}
+
+impl Error {
+    /// The JSONPath of the node that caused the error, if the error belongs to a node.
+    pub fn path(&self) -> Option<&str> {
+        match self {
+            Error::UnknownOperator { path, .. }
+            | Error::InvalidArguments { path, .. }
+            | Error::NonStaticVariable { path }
+            | Error::InvalidRegex { path, .. }
+            | Error::IntegerOverflow { path, .. }
+            | Error::LimitExceeded { path, .. } => Some(path),
+            Error::ReservedOperator { .. } => None,
+        }
+    }
+
+    pub(crate) fn invalid_arguments(operator: &str, message: &str, path: &str) -> Error {
+        Error::InvalidArguments {
+            operator: operator.to_owned(),
+            message: message.to_owned(),
+            path: path.to_owned(),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::UnknownOperator { name, path } => {
+                write!(f, "Unrecognized operation {} at {}", name, path)
+            }
+            Error::InvalidArguments {
+                operator,
+                message,
+                path,
+            } => write!(
+                f,
+                "Invalid arguments for operation {} at {}: {}",
+                operator, path, message
+            ),
+            Error::NonStaticVariable { path } => {
+                write!(
+                    f,
+                    "Found variable operator with non static argument at {}",
+                    path
+                )
+            }
+            Error::InvalidRegex {
+                pattern,
+                message,
+                path,
+            } => write!(f, "Invalid regex {} at {}: {}", pattern, path, message),
+            Error::IntegerOverflow { operator, path } => {
+                write!(f, "Integer overflow in operation {} at {}", operator, path)
+            }
+            Error::LimitExceeded { limit, path } => {
+                write!(f, "Exceeded the {} limit at {}", limit, path)
+            }
+            Error::ReservedOperator { name } => {
+                write!(f, "Cannot override built-in operation {}", name)
+            }
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+/// The JSONPath of the root of a rule.
+pub(crate) const ROOT_PATH: &str = "$";
+
+/// Appends an object key to the given JSONPath. Keys that are not plain identifiers, like most
+/// operators, use the bracket notation.
+pub(crate) fn key_path(path: &str, key: &str) -> String {
+    let is_identifier = matches!(key.chars().next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+
+    if is_identifier {
+        format!("{}.{}", path, key)
+    } else {
+        let escaped = key.replace('\\', "\\\\").replace('\'', "\\'");
+        format!("{}['{}']", path, escaped)
+    }
+}
+
+/// Appends an array index to the given JSONPath.
+pub(crate) fn index_path(path: &str, index: usize) -> String {
+    format!("{}[{}]", path, index)
+}
+
+/// Returns the JSONPath of the argument at the given index of the operation at the given path,
+/// e.g. `$.and[1]` for the second argument of the `and` at the root.
+///
+/// The paths of parsed expressions, like in traces and variable references, always address an
+/// argument by its index, also if the rule gave a single argument without the surrounding array
+/// like in `{ "var": "a" }`.
+pub(crate) fn argument_path(path: &str, operator: &str, index: usize) -> String {
+    index_path(&key_path(path, operator), index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paths() {
+        assert_eq!(key_path(ROOT_PATH, "and"), "$.and");
+        assert_eq!(key_path(ROOT_PATH, "missing_some"), "$.missing_some");
+        assert_eq!(key_path(ROOT_PATH, "=="), "$['==']");
+        assert_eq!(key_path(ROOT_PATH, "it's"), "$['it\\'s']");
+        assert_eq!(key_path(ROOT_PATH, "1a"), "$['1a']");
+        assert_eq!(index_path(&key_path(ROOT_PATH, "if"), 2), "$.if[2]");
+    }
+
+    #[test]
+    fn display() {
+        let err = Error::UnknownOperator {
+            name: String::from("foo"),
+            path: String::from("$.and[0]"),
+        };
+        assert_eq!(err.to_string(), "Unrecognized operation foo at $.and[0]");
+        assert_eq!(err.path(), Some("$.and[0]"));
+    }
+}
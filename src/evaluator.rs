@@ -0,0 +1,225 @@
+use serde_json::Value;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::data_source::DataSource;
+use crate::error::Error;
+use crate::limits::Limits;
+use crate::log_sink::{LogSink, StdoutSink};
+use crate::operators::Arithmetic;
+use crate::regex_cache::RegexCache;
+use crate::registry::{CustomOperator, OperatorRegistry};
+use crate::trace::Trace;
+use crate::{CompiledRule, PartialApplyOutcome};
+
+/// Parses and evaluates JsonLogic rules that may use custom operators in addition to the built-in
+/// ones. The free functions `apply`, `partial_apply` and `partial_apply_residual` use an evaluator
+/// without any custom operators.
+///
+/// # Example
+///
+/// ```
+/// use jsonlogic::data::Data;
+/// use jsonlogic::expression::Expression;
+/// use jsonlogic::registry::CustomOperator;
+/// use jsonlogic::Evaluator;
+/// use serde_json::{json, Value};
+///
+/// struct Upper;
+///
+/// impl CustomOperator for Upper {
+///     fn compute(&self, args: &[Expression], data: &Data) -> Value {
+///         match args.first().map(|arg| arg.compute(data)) {
+///             Some(Value::String(s)) => Value::String(s.to_uppercase()),
+///             _ => Value::Null,
+///         }
+///     }
+/// }
+///
+/// let mut evaluator = Evaluator::new();
+/// evaluator.register("upper", Upper).unwrap();
+///
+/// let rule = json!({ "upper": { "var": "name" } });
+/// assert_eq!(evaluator.apply(&rule, &json!({ "name": "bob" })), Ok(json!("BOB")));
+/// assert!(jsonlogic::apply(&rule, &json!({ "name": "bob" })).is_err());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Evaluator {
+    registry: OperatorRegistry,
+    settings: Settings,
+}
+
+/// The configuration of an evaluator, which is passed on to the rules it compiles.
+#[derive(Clone)]
+pub(crate) struct Settings {
+    pub log_sink: Arc<dyn LogSink>,
+    /// Shared by all rules compiled by the evaluator.
+    pub regex_cache: Arc<RegexCache>,
+    /// Whether a computed `match` pattern that is invalid aborts the evaluation.
+    pub strict_regex: bool,
+    /// Whether arithmetic keeps integers or decimals instead of computing with floats.
+    pub arithmetic: Arithmetic,
+    pub limits: Limits,
+}
+
+impl Default for Settings {
+    fn default() -> Settings {
+        Settings {
+            log_sink: Arc::new(StdoutSink),
+            regex_cache: Arc::new(RegexCache::default()),
+            strict_regex: false,
+            arithmetic: Arithmetic::Float,
+            limits: Limits::default(),
+        }
+    }
+}
+
+impl fmt::Debug for Settings {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Settings").finish_non_exhaustive()
+    }
+}
+
+impl Evaluator {
+    /// Creates an evaluator that knows only the built-in operators.
+    pub fn new() -> Evaluator {
+        Evaluator::default()
+    }
+
+    /// Creates an evaluator that knows the built-in operators and those of the given registry.
+    pub fn with_registry(registry: OperatorRegistry) -> Evaluator {
+        Evaluator {
+            registry,
+            settings: Settings::default(),
+        }
+    }
+
+    /// Registers a custom operator. See `OperatorRegistry::register`.
+    pub fn register<O>(&mut self, name: &str, operator: O) -> Result<(), Error>
+    where
+        O: CustomOperator + 'static,
+    {
+        self.registry.register(name, operator)
+    }
+
+    /// Sets the sink that receives the values logged by the `log` operator. By default they are
+    /// printed to stdout, use `DisabledSink` to discard them.
+    pub fn set_log_sink<S>(&mut self, sink: S)
+    where
+        S: LogSink + 'static,
+    {
+        self.settings.log_sink = Arc::new(sink);
+    }
+
+    /// Sets how many regexes are cached for `match` operations whose pattern is computed when the
+    /// rule is applied. Least recently used regexes are evicted first, a size of 0 disables the
+    /// cache. Literal patterns are compiled with the rule and do not count towards the size.
+    ///
+    /// The cache is shared by the rules compiled by this evaluator. Setting the size replaces the
+    /// cache for rules compiled afterwards.
+    pub fn set_regex_cache_size(&mut self, size: usize) {
+        self.settings.regex_cache = Arc::new(RegexCache::new(size));
+    }
+
+    /// Sets whether a `match` operation whose computed pattern or flags are invalid aborts the
+    /// evaluation with `Error::InvalidRegex`. By default the operation returns `false`. Invalid
+    /// literal patterns are always reported when the rule is compiled.
+    pub fn set_strict_regex(&mut self, strict: bool) {
+        self.settings.strict_regex = strict;
+    }
+
+    /// Returns how many regexes with computed patterns are cached, see `set_regex_cache_size`.
+    pub fn regex_cache_size(&self) -> usize {
+        self.settings.regex_cache.capacity()
+    }
+
+    /// Sets whether `+`, `-`, `*`, `%`, `min` and `max` keep integers. If all operands are
+    /// integers, or strings of integers, the result is the exact `i64` or `u64` instead of a float,
+    /// and an overflow aborts the evaluation with `Error::IntegerOverflow`. Otherwise the
+    /// operations are computed with floats as usual. Disabled by default, like javascript computes
+    /// with floats.
+    pub fn set_integer_arithmetic(&mut self, enabled: bool) {
+        self.settings.arithmetic = match enabled {
+            true => Arithmetic::Integer,
+            false => Arithmetic::Float,
+        };
+    }
+
+    /// Sets whether `+`, `-`, `*`, `/`, `%`, `min` and `max` compute with exact decimals. If all
+    /// operands are numbers, or strings of numbers like `"12.50"`, `{"*": [0.1, 3]}` results in
+    /// `0.3` instead of `0.30000000000000004`. Operands that are no decimals, or results that are
+    /// no exact decimal, like `1 / 3` or `1e-20 * 1e-20`, are computed with floats as usual.
+    /// Disabled by default.
+    ///
+    /// Replaces the integer arithmetic set by `set_integer_arithmetic`, and vice versa. Comparisons
+    /// of numbers are always exact when the `decimal` feature is enabled.
+    ///
+    /// The feature enables the `arbitrary_precision` feature of `serde_json`, for every crate in
+    /// the dependency graph.
+    #[cfg(feature = "decimal")]
+    pub fn set_decimal_arithmetic(&mut self, enabled: bool) {
+        self.settings.arithmetic = match enabled {
+            true => Arithmetic::Decimal,
+            false => Arithmetic::Float,
+        };
+    }
+
+    /// Sets the limits of the resources the rules compiled afterwards may use. See `Limits`.
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.settings.limits = limits;
+    }
+
+    /// Returns the limits of the rules compiled by this evaluator.
+    pub fn limits(&self) -> &Limits {
+        &self.settings.limits
+    }
+
+    /// Returns the custom operators known to this evaluator.
+    pub fn registry(&self) -> &OperatorRegistry {
+        &self.registry
+    }
+
+    /// Parses the given rule so it can be evaluated many times.
+    pub fn compile(&self, json_logic: &Value) -> Result<CompiledRule, Error> {
+        CompiledRule::compile(json_logic, &self.registry, self.settings.clone())
+    }
+
+    /// Applies the given JsonLogic rule to the specified data. See `jsonlogic::apply`.
+    pub fn apply(&self, json_logic: &Value, data: &dyn DataSource) -> Result<Value, Error> {
+        let rule = self.compile(json_logic)?;
+        rule.evaluate(data)
+    }
+
+    /// Applies the given JsonLogic rule to the specified data and records every operation that is
+    /// evaluated. See `jsonlogic::apply_with_trace`.
+    pub fn apply_with_trace(
+        &self,
+        json_logic: &Value,
+        data: &dyn DataSource,
+    ) -> Result<(Value, Trace), Error> {
+        let rule = self.compile(json_logic)?;
+        rule.evaluate_with_trace(data)
+    }
+
+    /// Applies the given JsonLogic rule to possibly incomplete data. See
+    /// `jsonlogic::partial_apply`.
+    pub fn partial_apply(
+        &self,
+        json_logic: &Value,
+        data: &dyn DataSource,
+    ) -> Result<PartialApplyOutcome, Error> {
+        let rule = self.compile(json_logic)?;
+        rule.partial_evaluate(data)
+    }
+
+    /// Applies the given JsonLogic rule to possibly incomplete data and returns the residual rule
+    /// if the result is ambiguous. See `jsonlogic::partial_apply_residual`.
+    pub fn partial_apply_residual(
+        &self,
+        json_logic: &Value,
+        data: &dyn DataSource,
+    ) -> Result<PartialApplyOutcome, Error> {
+        let rule = self.compile(json_logic)?;
+        rule.partial_evaluate_residual(data)
+    }
+}
@@ -1,60 +1,274 @@
-use crate::operators::Operator;
+use crate::error::{argument_path, index_path, key_path, Error, ROOT_PATH};
+use crate::evaluator::Settings;
+use crate::limits::Limits;
+use crate::operators::{self, Operator};
+use crate::registry::{OperatorRegistry, RegisteredOperator};
+use crate::simplify::Simplifier;
 use crate::Data;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::Value;
-use std::collections::HashSet;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 
 #[derive(Debug)]
 pub struct Ambiguous;
 
 pub type PartialResult = Result<Value, Ambiguous>;
 
+/// The part of an expression that could not be resolved with the given data. Contains the
+/// simplified expression that still has to be computed once the missing data is known.
 #[derive(Debug, PartialEq)]
-pub enum Expression<'a> {
-    Constant(&'a Value),
-    Computed(Operator, Vec<Expression<'a>>),
+pub struct Residual(pub Expression);
+
+pub type ResidualResult = Result<Value, Residual>;
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Expression {
+    Constant(Value),
+    Computed(Operator, Vec<Expression>),
+    /// An operator from an `OperatorRegistry`.
+    Custom(RegisteredOperator, Vec<Expression>),
 }
 
-impl<'a> Expression<'a> {
-    pub fn from_json(json: &Value) -> Result<Expression, String> {
+impl Expression {
+    pub fn from_json(json: &Value) -> Result<Expression, Error> {
+        Expression::from_json_with_registry(json, &OperatorRegistry::new())
+    }
+
+    /// Like `from_json`, but operators that are not built-in are looked up in the given registry.
+    ///
+    /// Rules nested deeper than `limits::DEFAULT_MAX_DEPTH` are rejected with
+    /// `Error::LimitExceeded` before they are parsed.
+    pub fn from_json_with_registry(
+        json: &Value,
+        registry: &OperatorRegistry,
+    ) -> Result<Expression, Error> {
+        Limits::default().check_depth(json)?;
+        Expression::parse(json, registry, ROOT_PATH)
+    }
+
+    /// Like `from_json_with_registry`, for callers that checked the depth of the rule against
+    /// their own limit.
+    pub(crate) fn from_json_unchecked(
+        json: &Value,
+        registry: &OperatorRegistry,
+    ) -> Result<Expression, Error> {
+        Expression::parse(json, registry, ROOT_PATH)
+    }
+
+    fn parse(json: &Value, registry: &OperatorRegistry, path: &str) -> Result<Expression, Error> {
         if !json.is_object() {
-            return Ok(Expression::Constant(&json));
+            return Ok(Expression::Constant(json.clone()));
         }
 
         let object = json.as_object().unwrap();
         // If this object has more than one key-value pair, we will return it as is. This replicates
         // the behaviour of the javascript implementation.
         if object.len() != 1 {
-            return Ok(Expression::Constant(json));
+            return Ok(Expression::Constant(json.clone()));
         }
 
         let entry: Vec<(&String, &serde_json::Value)> = object.iter().collect();
-        let &(operator_key, value) = entry.get(0).unwrap();
-        let operator = Operator::from_str(operator_key)
-            .ok_or_else(|| format!("Unrecognized operation {}", operator_key))?;
+        let &(operator_key, value) = entry.first().unwrap();
+        let operation_path = key_path(path, operator_key);
+
+        let operator = match Operator::from_str(operator_key) {
+            Some(operator) => {
+                let arguments = Expression::arguments_from_json(value, registry, &operation_path)?;
+                if operator == Operator::Let && !valid_bindings(&arguments) {
+                    return Err(Error::invalid_arguments(
+                        operator_key,
+                        "expects pairs of a constant name and a value, followed by the body",
+                        path,
+                    ));
+                }
+                return Ok(Expression::Computed(operator, arguments));
+            }
+            None => registry
+                .get(operator_key)
+                .ok_or_else(|| Error::UnknownOperator {
+                    name: operator_key.to_owned(),
+                    path: path.to_owned(),
+                })?,
+        };
+
+        let arguments = Expression::arguments_from_json(value, registry, &operation_path)?;
+        if let Some(arity) = operator.arity() {
+            if !arity.contains(arguments.len()) {
+                return Err(Error::invalid_arguments(
+                    operator_key,
+                    &format!("expects {} arguments, got {}", arity, arguments.len()),
+                    path,
+                ));
+            }
+        }
+
+        Ok(Expression::Custom(operator.clone(), arguments))
+    }
 
-        let arguments: Vec<_> = match value {
-            Value::Array(arr) => arr.iter().map(|expr| Expression::from_json(expr)).collect(),
+    fn arguments_from_json(
+        value: &Value,
+        registry: &OperatorRegistry,
+        path: &str,
+    ) -> Result<Vec<Expression>, Error> {
+        match value {
+            Value::Array(arr) => arr
+                .iter()
+                .enumerate()
+                .map(|(i, json)| Expression::parse(json, registry, &index_path(path, i)))
+                .collect(),
             // Interpret as an empty array.
             Value::Null => Ok(vec![]),
             // If the value is not an array we can only assume that this is a shorthand.
-            _ => Expression::from_json(value).and_then(|expr| Ok(vec![expr])),
-        }?;
+            _ => Expression::parse(value, registry, path).map(|expr| vec![expr]),
+        }
+    }
 
-        Ok(Expression::Computed(operator, arguments))
+    /// Returns a simpler expression that evaluates to the same value for all data, e.g. for rules
+    /// generated by a UI:
+    ///
+    /// - Operations whose arguments are constants are computed, unless they access the data like
+    ///   `var` and `missing`, or log. Evaluations that fail, e.g. by an integer overflow, are kept.
+    /// - Nested `and`, `or`, `cat` and `merge` operations are merged into the enclosing one. Nested
+    ///   `+` and `*` operations only in the first argument, since floats are not associative.
+    /// - Constants that do not change the result are removed, e.g. `true` in an `and` or `0` in a
+    ///   `+`. An `and` or `or` with a single argument is replaced by the argument.
+    /// - `if` branches whose condition is a constant are resolved.
+    ///
+    /// Constants are computed with the default settings, use `CompiledRule::simplify` for the
+    /// arithmetic of an `Evaluator`. Custom operators are not computed. Note that the trace of a
+    /// simplified rule differs from the original one.
+    pub fn simplify(&self) -> Expression {
+        Simplifier::new(&Settings::default(), &HashMap::new()).simplify(self)
     }
 
     /// Computes the expression and returns value it evaluates to.
     pub fn compute(&self, data: &Data) -> Value {
+        let context = match data.context() {
+            Some(context) => context,
+            None => return self.compute_node(data),
+        };
+        let _entered = context.enter(self, data);
+        if context.has_failed() || !context.step(self) {
+            return Value::Null;
+        }
+
+        let value = match &context.tracer {
+            Some(tracer) => tracer.trace(self, data, context),
+            None => self.compute_node(data),
+        };
+        context.check_size(self, &value);
+        value
+    }
+
+    /// Like `compute`, but borrows the value instead of cloning it if the expression is a constant
+    /// or a `var` that refers to a part of the data. Operators that only inspect an argument use
+    /// this to avoid copying large parts of the data.
+    pub fn compute_borrowed<'e>(&'e self, data: &Data<'e>) -> Cow<'e, Value> {
+        let context = data.context();
+        match self {
+            // The trace records the results anyway.
+            _ if matches!(context, Some(context) if context.tracer.is_some()) => {
+                Cow::Owned(self.compute(data))
+            }
+            Expression::Constant(_) if matches!(context, Some(context) if context.has_failed()) => {
+                Cow::Owned(Value::Null)
+            }
+            Expression::Constant(value) => Cow::Borrowed(value),
+            Expression::Computed(Operator::Variable, args) => {
+                let _entered = context.and_then(|context| context.enter(self, data));
+                if let Some(context) = context {
+                    if context.has_failed() || !context.step(self) {
+                        return Cow::Owned(Value::Null);
+                    }
+                }
+                let value = operators::compute_variable(args, &data.operation());
+                if let Some(context) = context {
+                    context.check_size(self, &value);
+                }
+                value
+            }
+            _ => Cow::Owned(self.compute(data)),
+        }
+    }
+
+    /// Computes the expression without recording it in the trace of the evaluation.
+    pub(crate) fn compute_node(&self, data: &Data) -> Value {
         match self {
-            Expression::Constant(value) => (*value).clone(),
-            Expression::Computed(operator, args) => operator.compute(args, data),
+            Expression::Constant(value) => value.clone(),
+            Expression::Computed(operator, args) => operator.compute(args, &data.operation()),
+            Expression::Custom(operator, args) => operator.compute(args, &data.operation()),
         }
     }
 
     pub fn partial_compute(&self, data: &Data) -> PartialResult {
-        match self {
-            Expression::Constant(value) => Ok((*value).clone()),
+        let _entered = data.context().and_then(|context| context.enter(self, data));
+        if !self.proceeds(data) {
+            return Ok(Value::Null);
+        }
+
+        let data = &data.operation();
+        let result = match self {
+            Expression::Constant(value) => Ok(value.clone()),
             Expression::Computed(operator, args) => operator.partial_compute(args, data),
+            Expression::Custom(operator, args) => operator.partial_compute(args, data),
+        };
+        self.check_size(data, &result);
+        result
+    }
+
+    /// Like `partial_compute`, but instead of just reporting that the result is ambiguous, returns
+    /// the pruned expression made of the parts that depend on missing data.
+    pub fn residual_compute(&self, data: &Data) -> ResidualResult {
+        let _entered = data.context().and_then(|context| context.enter(self, data));
+        if !self.proceeds(data) {
+            return Ok(Value::Null);
+        }
+
+        let data = &data.operation();
+        let result = match self {
+            Expression::Constant(value) => Ok(value.clone()),
+            Expression::Computed(operator, args) => operator.residual_compute(args, data),
+            Expression::Custom(operator, args) => operators::residual_compute_operation(
+                args,
+                data,
+                |_| true,
+                |args, data| operator.partial_compute(args, data),
+                |args| Expression::Custom(operator.clone(), args),
+            ),
+        };
+        self.check_size(data, &result);
+        result
+    }
+
+    /// Returns whether the expression is to be computed, i.e. the evaluation has not been aborted
+    /// and the step limit is not exceeded by computing it.
+    fn proceeds(&self, data: &Data) -> bool {
+        match data.context() {
+            Some(context) => !context.has_failed() && context.step(self),
+            None => true,
+        }
+    }
+
+    /// Aborts the evaluation if the resolved value exceeds the size limit.
+    fn check_size<E>(&self, data: &Data, result: &Result<Value, E>) {
+        if let (Some(context), Ok(value)) = (data.context(), result) {
+            context.check_size(self, value);
+        }
+    }
+
+    /// Converts the expression back into a JsonLogic rule, e.g. to store a rule that was
+    /// transformed in Rust. Parsing the result gives an equal expression.
+    ///
+    /// The rule is canonical: the arguments of an operation are always an array, so a shorthand
+    /// single argument like in `{ "var": "a" }` becomes `{ "var": ["a"] }`, and `null` arguments
+    /// become `[]`. Note that a constant object with a single key is read back as an operation,
+    /// parsing never creates such a constant.
+    pub fn to_json(&self) -> Value {
+        match self {
+            Expression::Constant(value) => value.clone(),
+            Expression::Computed(operator, args) => operation_to_json(operator.as_str(), args),
+            Expression::Custom(operator, args) => operation_to_json(operator.name(), args),
         }
     }
 
@@ -66,38 +280,31 @@ impl<'a> Expression<'a> {
     ///
     /// While the latter is valid for computation, it is currently not implemented to analyze the
     /// variable name for that.
-    pub fn get_variable_names(&self) -> Result<HashSet<String>, String> {
+    pub fn get_variable_names(&self) -> Result<HashSet<String>, Error> {
         let mut variable_names: HashSet<String> = HashSet::new();
 
-        self.insert_var_names(&mut variable_names)?;
+        self.insert_var_names(&mut variable_names, ROOT_PATH)?;
         Ok(variable_names)
     }
 
-    fn insert_var_names(&self, names: &mut HashSet<String>) -> Result<(), String> {
+    fn insert_var_names(&self, names: &mut HashSet<String>, path: &str) -> Result<(), Error> {
         match self {
             Expression::Constant(_) => Ok(()),
+            Expression::Custom(operator, args) => {
+                args.iter().enumerate().try_for_each(|(i, expr)| {
+                    expr.insert_var_names(names, &argument_path(path, operator.name(), i))
+                })
+            }
             Expression::Computed(operator, args) => {
                 if let Operator::Variable = operator {
-                    let first_expr = args
-                        .get(0)
-                        .ok_or("found Variable operator without arguments")?;
-                    if let Expression::Constant(name_value) = first_expr {
-                        let name = name_value
-                            .as_str()
-                            .ok_or("found Variable operator with non string argument")?;
-                        names.insert(name.to_owned());
-                        return Ok(());
-                    } else {
-                        return Err(String::from(
-                            "found Variable operator with non static argument",
-                        ));
-                    }
+                    names.insert(static_variable_name(args, path)?.to_owned());
+                    return Ok(());
                 }
 
                 // For all other operations analyze the arguments recursive.
-                args.iter()
-                    .map(|expr| expr.insert_var_names(names))
-                    .collect()
+                args.iter().enumerate().try_for_each(|(i, expr)| {
+                    expr.insert_var_names(names, &argument_path(path, operator.as_str(), i))
+                })
             }
         }
     }
@@ -108,21 +315,28 @@ impl<'a> Expression<'a> {
     /// - has not a string as its argument (TODO: numbers are ok for when data is an array)
     /// - has a non static argument
     ///
-    pub fn get_variable_names_and_values(
-        &self,
-    ) -> Result<HashSet<(String, String, Value)>, String> {
+    pub fn get_variable_names_and_values(&self) -> Result<HashSet<(String, String, Value)>, Error> {
         let mut variable_names: HashSet<(String, String, Value)> = HashSet::new();
 
-        self.insert_var_names_and_values(&mut variable_names)?;
+        self.insert_var_names_and_values(&mut variable_names, ROOT_PATH)?;
         Ok(variable_names)
     }
 
     fn insert_var_names_and_values(
         &self,
         variable_names: &mut HashSet<(String, String, Value)>,
-    ) -> Result<Value, String> {
+        path: &str,
+    ) -> Result<Value, Error> {
         match self {
-            Expression::Constant(a) => Ok((**a).clone()),
+            Expression::Constant(a) => Ok(a.clone()),
+            Expression::Custom(operator, args) => args
+                .iter()
+                .enumerate()
+                .map(|(i, expr)| {
+                    let arg_path = argument_path(path, operator.name(), i);
+                    expr.insert_var_names_and_values(variable_names, &arg_path)
+                })
+                .collect(),
             Expression::Computed(operator, args) => {
                 match operator {
                     Operator::Equal
@@ -143,35 +357,37 @@ impl<'a> Expression<'a> {
                         let op = format!("{:?}", operator);
                         let present_value = args
                             .last()
-                            .ok_or("Found variable operator without value")?
-                            .insert_var_names_and_values(variable_names)?;
-                        if let Expression::Computed(Operator::Variable, next_arg) =
-                            args.get(0).ok_or("Equality operator without arguments")?
+                            .ok_or_else(|| {
+                                Error::invalid_arguments(
+                                    operator.as_str(),
+                                    "found operator without arguments",
+                                    path,
+                                )
+                            })?
+                            .insert_var_names_and_values(
+                                variable_names,
+                                &argument_path(path, operator.as_str(), args.len() - 1),
+                            )?;
+                        if let Some(Expression::Computed(Operator::Variable, var_args)) =
+                            args.first()
                         {
-                            let first_expr = next_arg
-                                .get(0)
-                                .ok_or("found Variable operator without arguments")?;
-                            match first_expr {
-                                Expression::Constant(name) => variable_names.insert((
-                                    name.as_str()
-                                        .ok_or("found Variable operator with non string argument")?
-                                        .to_owned(),
-                                    op,
-                                    present_value.clone(),
-                                )),
-                                _ => {
-                                    return Err(String::from(
-                                        "found Variable operator with non static argument",
-                                    ))
-                                }
-                            };
+                            let var_path = argument_path(path, operator.as_str(), 0);
+                            variable_names.insert((
+                                static_variable_name(var_args, &var_path)?.to_owned(),
+                                op,
+                                present_value.clone(),
+                            ));
                         }
                         Ok(present_value)
                     }
                     _ => {
                         // For all other operations analyze the arguments recursive.
                         args.iter()
-                            .map(|expr| expr.insert_var_names_and_values(variable_names))
+                            .enumerate()
+                            .map(|(i, expr)| {
+                                let arg_path = argument_path(path, operator.as_str(), i);
+                                expr.insert_var_names_and_values(variable_names, &arg_path)
+                            })
                             .collect()
                     }
                 }
@@ -180,12 +396,83 @@ impl<'a> Expression<'a> {
     }
 }
 
+/// Serializes the expression as its canonical JsonLogic rule, see `Expression::to_json`.
+impl Serialize for Expression {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_json().serialize(serializer)
+    }
+}
+
+/// Parses the expression from a JsonLogic rule like `Expression::from_json`, so only built-in
+/// operators are known.
+impl<'de> Deserialize<'de> for Expression {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let json = Value::deserialize(deserializer)?;
+        Expression::from_json(&json).map_err(de::Error::custom)
+    }
+}
+
+/// Returns the name of the variable that is accessed by a `var` operation with the given
+/// arguments at the given path. Errors if the name is not a constant string.
+fn static_variable_name<'a>(args: &'a [Expression], path: &str) -> Result<&'a str, Error> {
+    let first_expr = args.first().ok_or_else(|| {
+        Error::invalid_arguments("var", "found Variable operator without arguments", path)
+    })?;
+    match first_expr {
+        Expression::Constant(name) => name.as_str().ok_or_else(|| {
+            Error::invalid_arguments(
+                "var",
+                "found Variable operator with non string argument",
+                path,
+            )
+        }),
+        _ => Err(Error::NonStaticVariable {
+            path: path.to_owned(),
+        }),
+    }
+}
+
+/// Returns whether the arguments of a `let` operation are pairs of a constant string and a value,
+/// followed by the body.
+fn valid_bindings(args: &[Expression]) -> bool {
+    args.len() % 2 == 1
+        && args[..args.len() - 1]
+            .iter()
+            .step_by(2)
+            .all(|name| matches!(name, Expression::Constant(Value::String(_))))
+}
+
+fn operation_to_json(name: &str, args: &[Expression]) -> Value {
+    let args = args.iter().map(|arg| arg.to_json()).collect();
+    let mut object = serde_json::Map::with_capacity(1);
+    object.insert(name.to_owned(), Value::Array(args));
+    Value::Object(object)
+}
+
 #[cfg(test)]
 mod tests {
     use super::Expression::*;
     use super::*;
     use serde_json::json;
 
+    #[test]
+    fn to_json() {
+        let canonical = |json: Value| Expression::from_json(&json).unwrap().to_json();
+
+        assert_eq!(canonical(json!({ "var": "a" })), json!({ "var": ["a"] }));
+        assert_eq!(canonical(json!({ "var": null })), json!({ "var": [] }));
+        assert_eq!(
+            canonical(json!({ "if": [{ "!": { "var": "a" } }, [1, { "a": 1, "b": 2 }], "b"] })),
+            json!({ "if": [{ "!": [{ "var": ["a"] }] }, [1, { "a": 1, "b": 2 }], "b"] })
+        );
+        assert_eq!(canonical(json!(null)), json!(null));
+        assert_eq!(canonical(json!({})), json!({}));
+
+        let rule = json!({ "and": [{ "<": [1, { "var": "x" }, 3] }, { "in": ["a", "abc"] }] });
+        let expr = Expression::from_json(&rule).unwrap();
+        assert_eq!(Expression::from_json(&expr.to_json()).unwrap(), expr);
+    }
+
     #[test]
     fn parse_to_ast() {
         assert_eq!(
@@ -200,14 +487,14 @@ mod tests {
 
         assert_eq!(
             Expression::from_json(&json!({ "==": [1] })).unwrap(),
-            Expression::Computed(Operator::Equal, vec![Constant(&json!(1))])
+            Expression::Computed(Operator::Equal, vec![Constant(json!(1))])
         );
 
         assert_eq!(
             Expression::from_json(&json!({ "==": [1, 2] })).unwrap(),
             Expression::Computed(
                 Operator::Equal,
-                vec![Constant(&json!(1)), Constant(&json!(2))]
+                vec![Constant(json!(1)), Constant(json!(2))]
             )
         );
 
@@ -215,13 +502,13 @@ mod tests {
             Expression::from_json(&json!({"!=": [5, 2]})).unwrap(),
             Expression::Computed(
                 Operator::NotEqual,
-                vec![Constant(&json!(5)), Constant(&json!(2))]
+                vec![Constant(json!(5)), Constant(json!(2))]
             )
         );
 
         assert_eq!(
             Expression::from_json(&json!({"var": ["foo"]})).unwrap(),
-            Expression::Computed(Operator::Variable, vec![Constant(&json!("foo"))])
+            Expression::Computed(Operator::Variable, vec![Constant(json!("foo"))])
         );
 
         assert_eq!(
@@ -229,8 +516,8 @@ mod tests {
             Expression::Computed(
                 Operator::Equal,
                 vec![
-                    Expression::Computed(Operator::Variable, vec![Constant(&json!("foo"))]),
-                    Expression::Constant(&json!("foo"))
+                    Expression::Computed(Operator::Variable, vec![Constant(json!("foo"))]),
+                    Expression::Constant(json!("foo"))
                 ]
             )
         );
@@ -243,42 +530,45 @@ mod tests {
                 Operator::Variable,
                 vec![Expression::Computed(
                     Operator::Variable,
-                    vec![Expression::Constant(&json!("foo"))]
+                    vec![Expression::Constant(json!("foo"))]
                 )]
             )
             .get_variable_names(),
-            Err(String::from(
-                "found Variable operator with non static argument"
-            ))
+            Err(Error::NonStaticVariable {
+                path: String::from("$")
+            })
         );
 
         assert_eq!(
-            Expression::Computed(Operator::Variable, vec![Expression::Constant(&json!(1))])
+            Expression::Computed(Operator::Variable, vec![Expression::Constant(json!(1))])
                 .get_variable_names(),
-            Err(String::from(
-                "found Variable operator with non string argument"
+            Err(Error::invalid_arguments(
+                "var",
+                "found Variable operator with non string argument",
+                "$"
             ))
         );
 
         assert_eq!(
             Expression::Computed(Operator::Variable, vec![]).get_variable_names(),
-            Err(String::from("found Variable operator without arguments"))
+            Err(Error::invalid_arguments(
+                "var",
+                "found Variable operator without arguments",
+                "$"
+            ))
         );
     }
 
     #[test]
     fn get_variable_names() {
         assert_eq!(
-            Expression::Constant(&json!("foo")).get_variable_names(),
+            Expression::Constant(json!("foo")).get_variable_names(),
             Ok(HashSet::new())
         );
 
         assert_eq!(
-            Expression::Computed(
-                Operator::Variable,
-                vec![Expression::Constant(&json!("foo"))]
-            )
-            .get_variable_names(),
+            Expression::Computed(Operator::Variable, vec![Expression::Constant(json!("foo"))])
+                .get_variable_names(),
             Ok(["foo".to_owned()].iter().cloned().collect::<HashSet<_>>())
         );
 
@@ -286,10 +576,10 @@ mod tests {
             Expression::Computed(
                 Operator::Equal,
                 vec![
-                    Expression::Constant(&json!("a value")),
+                    Expression::Constant(json!("a value")),
                     Expression::Computed(
                         Operator::Variable,
-                        vec![Expression::Constant(&json!("foo"))]
+                        vec![Expression::Constant(json!("foo"))]
                     )
                 ]
             )
@@ -303,11 +593,11 @@ mod tests {
                 vec![
                     Expression::Computed(
                         Operator::Variable,
-                        vec![Expression::Constant(&json!("foo"))]
+                        vec![Expression::Constant(json!("foo"))]
                     ),
                     Expression::Computed(
                         Operator::Variable,
-                        vec![Expression::Constant(&json!("foo"))]
+                        vec![Expression::Constant(json!("foo"))]
                     )
                 ]
             )
@@ -321,11 +611,11 @@ mod tests {
                 vec![
                     Expression::Computed(
                         Operator::Variable,
-                        vec![Expression::Constant(&json!("bar"))]
+                        vec![Expression::Constant(json!("bar"))]
                     ),
                     Expression::Computed(
                         Operator::Variable,
-                        vec![Expression::Constant(&json!("foo"))]
+                        vec![Expression::Constant(json!("foo"))]
                     )
                 ]
             )
@@ -347,29 +637,31 @@ mod tests {
                         Operator::Variable,
                         vec![Expression::Computed(
                             Operator::Variable,
-                            vec![Expression::Constant(&json!("foo"))]
+                            vec![Expression::Constant(json!("foo"))]
                         )]
                     ),
-                    Expression::Constant(&json!("bar"))
+                    Expression::Constant(json!("bar"))
                 ]
             )
             .get_variable_names_and_values(),
-            Err(String::from(
-                "found Variable operator with non static argument"
-            ))
+            Err(Error::NonStaticVariable {
+                path: String::from("$['=='][0]")
+            })
         );
 
         assert_eq!(
             Expression::Computed(
                 Operator::Equal,
                 vec![
-                    Expression::Computed(Operator::Variable, vec![Expression::Constant(&json!(1))]),
-                    Expression::Constant(&json!("bar"))
+                    Expression::Computed(Operator::Variable, vec![Expression::Constant(json!(1))]),
+                    Expression::Constant(json!("bar"))
                 ]
             )
             .get_variable_names_and_values(),
-            Err(String::from(
-                "found Variable operator with non string argument"
+            Err(Error::invalid_arguments(
+                "var",
+                "found Variable operator with non string argument",
+                "$['=='][0]"
             ))
         );
 
@@ -378,27 +670,28 @@ mod tests {
                 Operator::Equal,
                 vec![
                     Expression::Computed(Operator::Variable, vec![]),
-                    Expression::Constant(&json!("bar"))
+                    Expression::Constant(json!("bar"))
                 ]
             )
             .get_variable_names_and_values(),
-            Err(String::from("found Variable operator without arguments"))
+            Err(Error::invalid_arguments(
+                "var",
+                "found Variable operator without arguments",
+                "$['=='][0]"
+            ))
         );
     }
 
     #[test]
     fn get_variable_names_and_values() {
         assert_eq!(
-            Expression::Constant(&json!("foo")).get_variable_names_and_values(),
+            Expression::Constant(json!("foo")).get_variable_names_and_values(),
             Ok(HashSet::new())
         );
 
         assert_eq!(
-            Expression::Computed(
-                Operator::Variable,
-                vec![Expression::Constant(&json!("foo"))]
-            )
-            .get_variable_names(),
+            Expression::Computed(Operator::Variable, vec![Expression::Constant(json!("foo"))])
+                .get_variable_names(),
             Ok(["foo".to_owned()].iter().cloned().collect::<HashSet<_>>())
         );
 
@@ -410,9 +703,9 @@ mod tests {
                     vec![
                         Expression::Computed(
                             Operator::Variable,
-                            vec![Expression::Constant(&json!("foo"))]
+                            vec![Expression::Constant(json!("foo"))]
                         ),
-                        Expression::Constant(&json!(2))
+                        Expression::Constant(json!(2))
                     ]
                 )]
             )
@@ -432,9 +725,9 @@ mod tests {
                         vec![
                             Expression::Computed(
                                 Operator::Variable,
-                                vec![Expression::Constant(&json!("foo"))]
+                                vec![Expression::Constant(json!("foo"))]
                             ),
-                            Expression::Constant(&json!(2))
+                            Expression::Constant(json!(2))
                         ]
                     ),
                     Expression::Computed(
@@ -442,9 +735,9 @@ mod tests {
                         vec![
                             Expression::Computed(
                                 Operator::Variable,
-                                vec![Expression::Constant(&json!("foo"))]
+                                vec![Expression::Constant(json!("foo"))]
                             ),
-                            Expression::Constant(&json!(2))
+                            Expression::Constant(json!(2))
                         ]
                     )
                 ]
@@ -465,9 +758,9 @@ mod tests {
                         vec![
                             Expression::Computed(
                                 Operator::Variable,
-                                vec![Expression::Constant(&json!("foo"))]
+                                vec![Expression::Constant(json!("foo"))]
                             ),
-                            Expression::Constant(&json!(2))
+                            Expression::Constant(json!(2))
                         ]
                     ),
                     Expression::Computed(
@@ -475,9 +768,9 @@ mod tests {
                         vec![
                             Expression::Computed(
                                 Operator::Variable,
-                                vec![Expression::Constant(&json!("bar"))]
+                                vec![Expression::Constant(json!("bar"))]
                             ),
-                            Expression::Constant(&json!(5))
+                            Expression::Constant(json!(5))
                         ]
                     )
                 ]
@@ -498,7 +791,7 @@ mod tests {
 
         #[test]
         fn constant_expression() {
-            assert_eq!(Constant(&json!(1)).compute(&Data::empty()), json!(1));
+            assert_eq!(Constant(json!(1)).compute(&Data::empty()), json!(1));
         }
 
         #[test]
@@ -508,13 +801,13 @@ mod tests {
                 json!(true)
             );
             assert_eq!(
-                Computed(Operator::Equal, vec![Constant(&json!(null))]).compute(&Data::empty()),
+                Computed(Operator::Equal, vec![Constant(json!(null))]).compute(&Data::empty()),
                 json!(true)
             );
             assert_eq!(
                 Computed(
                     Operator::Equal,
-                    vec![Constant(&json!(1)), Constant(&json!(1))]
+                    vec![Constant(json!(1)), Constant(json!(1))]
                 )
                 .compute(&Data::empty()),
                 json!(true)
@@ -522,7 +815,7 @@ mod tests {
             assert_eq!(
                 Computed(
                     Operator::Equal,
-                    vec![Constant(&json!(1)), Constant(&json!(2))]
+                    vec![Constant(json!(1)), Constant(json!(2))]
                 )
                 .compute(&Data::empty()),
                 json!(false)
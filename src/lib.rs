@@ -1,20 +1,37 @@
 extern crate serde_json;
 
+mod context;
 pub mod data;
+pub mod data_source;
+mod error;
+pub mod evaluator;
 pub mod expression;
+pub mod limits;
+pub mod log_sink;
 pub mod operators;
+mod regex_cache;
+pub mod registry;
+pub mod rule;
+mod simplify;
+pub mod trace;
 pub mod validation;
+pub mod variables;
 
-use expression::Ambiguous;
 use serde_json::Value;
-use std::collections::HashSet;
 
 use data::Data;
+pub use data_source::DataSource;
+pub use error::Error;
+pub use evaluator::Evaluator;
+pub use rule::{CompiledRule, Rule};
 
 #[derive(Debug, PartialEq)]
 pub enum PartialApplyOutcome {
     Resolved(Value),
     Ambiguous,
+    /// The simplified JsonLogic rule that remains after resolving everything that is known from
+    /// the given data. Only returned by `partial_apply_residual`.
+    Residual(Value),
 }
 
 /// Applies the given JsonLogic rule to the specified data.
@@ -38,27 +55,137 @@ pub enum PartialApplyOutcome {
 /// let data = json!({ "foo": 3 });
 /// assert_eq!(jsonlogic::apply(&rule, &data), Ok(Value::Bool(false)));
 /// ```
-pub fn apply(json_logic: &Value, data: &Value) -> Result<Value, String> {
-    let ast = expression::Expression::from_json(json_logic)?;
-    let data = Data::from_json(data);
-    Ok(ast.compute(&data))
+///
+/// The rule is parsed on every call. To evaluate the same rule against many data objects, parse it
+/// once with [`CompiledRule::new`] and use [`CompiledRule::evaluate`] instead.
+pub fn apply(json_logic: &Value, data: &dyn DataSource) -> Result<Value, Error> {
+    Evaluator::new().apply(json_logic, data)
+}
+
+/// Like `apply`, but also returns a trace of the evaluation. It records every operation that was
+/// evaluated with its JSON path, the values of its arguments and its result, and which arguments of
+/// `and`, `or` and `if` were short-circuited. The trace can be serialized, e.g. with
+/// `serde_json::to_value`.
+///
+/// # Example
+///
+/// ```
+/// use serde_json::json;
+///
+/// let rule = json!({
+///     "and": [
+///         { ">": [{ "var": "amount" }, 100] },
+///         { "==": [{ "var": "currency" }, "EUR"] }
+///     ]
+/// });
+///
+/// let (result, trace) = jsonlogic::apply_with_trace(&rule, &json!({ "amount": 50 })).unwrap();
+/// assert_eq!(result, json!(false));
+///
+/// let root = trace.root.unwrap();
+/// assert_eq!(root.operator, "and");
+/// assert_eq!(root.short_circuited, vec![1]);
+/// assert_eq!(root.children[0].path, "$.and[0]");
+/// assert_eq!(root.children[0].arguments, vec![Some(json!(50)), Some(json!(100))]);
+/// ```
+pub fn apply_with_trace(
+    json_logic: &Value,
+    data: &dyn DataSource,
+) -> Result<(Value, trace::Trace), Error> {
+    Evaluator::new().apply_with_trace(json_logic, data)
+}
+
+pub fn partial_apply(
+    json_logic: &Value,
+    data: &dyn DataSource,
+) -> Result<PartialApplyOutcome, Error> {
+    Evaluator::new().partial_apply(json_logic, data)
+}
+
+/// Like `partial_apply`, but instead of `PartialApplyOutcome::Ambiguous` returns the residual
+/// rule, i.e. the given rule pruned of all parts that could be resolved with the given data.
+///
+/// # Example
+///
+/// ```
+/// use jsonlogic::PartialApplyOutcome;
+/// use serde_json::json;
+///
+/// let rule = json!({
+///     "and": [
+///         { "==": [{ "var": "status" }, "student"] },
+///         { ">": [{ "var": "age" }, 18] }
+///     ]
+/// });
+///
+/// let data = json!({ "status": "student" });
+/// assert_eq!(
+///     jsonlogic::partial_apply_residual(&rule, &data),
+///     Ok(PartialApplyOutcome::Residual(json!({ ">": [{ "var": ["age"] }, 18] })))
+/// );
+/// ```
+pub fn partial_apply_residual(
+    json_logic: &Value,
+    data: &dyn DataSource,
+) -> Result<PartialApplyOutcome, Error> {
+    Evaluator::new().partial_apply_residual(json_logic, data)
 }
 
-pub fn partial_apply(json_logic: &Value, data: &Value) -> Result<PartialApplyOutcome, String> {
-    let ast = expression::Expression::from_json(json_logic)?;
-    let data = Data::from_json(data);
-    let result = match ast.partial_compute(&data) {
-        Ok(value) => PartialApplyOutcome::Resolved(value),
-        Err(Ambiguous) => PartialApplyOutcome::Ambiguous,
-    };
-    Ok(result)
+/// Returns all references to the data in the given JsonLogic rule, i.e. the variables read with
+/// `var` and the keys checked with `missing` and `missing_some`, in the order they appear in.
+/// Errors if the rule cannot be parsed.
+///
+/// # Example
+///
+/// ```
+/// use jsonlogic::variables::VariablePath;
+/// use serde_json::json;
+///
+/// let rule = json!({
+///     "and": [
+///         { "==": [{ "var": ["status", "none"] }, "student"] },
+///         { "some": [{ "var": "courses" }, { ">": [{ "var": "credits" }, 5] }] }
+///     ]
+/// });
+///
+/// let refs = jsonlogic::variables(&rule).unwrap();
+/// assert_eq!(refs[0].path, VariablePath::Static(String::from("status")));
+/// assert_eq!(refs[0].default, Some(json!("none")));
+/// assert_eq!(refs[1].static_path(), Some("courses"));
+/// // `credits` is read from the elements of `courses`.
+/// assert_eq!(refs[2].static_path(), Some("credits"));
+/// assert!(refs[2].scoped);
+/// ```
+pub fn variables(json_logic: &Value) -> Result<Vec<variables::VariableReference>, Error> {
+    let rule = CompiledRule::new(json_logic)?;
+    Ok(rule.variables())
 }
 
-// TODO: Add to public api when ready.
-#[allow(dead_code)]
-fn get_variable_names(json_logic: &Value) -> Result<HashSet<String>, String> {
-    let ast = expression::Expression::from_json(json_logic)?;
-    ast.get_variable_names()
+/// Returns a simpler JsonLogic rule that evaluates to the same value for all data, see
+/// `Expression::simplify`. Errors if the rule cannot be parsed or if it contains an invalid regular
+/// expression.
+///
+/// # Example
+///
+/// ```
+/// use serde_json::json;
+///
+/// let rule = json!({
+///     "and": [
+///         true,
+///         { "if": [{ "==": [1, 2] }, { "var": "a" }, { "var": "b" }] },
+///         { "and": [{ ">": [{ "var": "c" }, { "+": [1, 2] }] }] }
+///     ]
+/// });
+///
+/// assert_eq!(
+///     jsonlogic::simplify(&rule),
+///     Ok(json!({ "and": [{ "var": ["b"] }, { ">": [{ "var": ["c"] }, 3.0] }] }))
+/// );
+/// ```
+pub fn simplify(json_logic: &Value) -> Result<Value, Error> {
+    let rule = CompiledRule::new(json_logic)?;
+    Ok(rule.simplify()?.expression().to_json())
 }
 
 #[cfg(test)]
@@ -70,10 +197,14 @@ mod tests {
     #[test]
     fn var_names() {
         let json_logic = json!({ "!==": [{ "var": "foo" }, { "var": ["bar", 5] }] });
-        let names: HashSet<_> = [String::from("foo"), String::from("bar")]
-            .iter()
-            .cloned()
-            .collect();
-        assert_eq!(get_variable_names(&json_logic).unwrap(), names);
+        let names: HashSet<_> = ["foo", "bar"].iter().cloned().collect();
+        let refs = variables(&json_logic).unwrap();
+        assert_eq!(
+            refs.iter()
+                .filter_map(|r| r.static_path())
+                .collect::<HashSet<_>>(),
+            names
+        );
+        assert_eq!(refs[1].default, Some(json!(5)));
     }
 }
@@ -0,0 +1,159 @@
+use serde_json::Value;
+
+use crate::error::{index_path, key_path, Error, ROOT_PATH};
+
+/// The nesting depth of rules by default. Like the recursion limit of `serde_json`, it bounds the
+/// recursion of parsing, validating and evaluating a rule.
+pub const DEFAULT_MAX_DEPTH: usize = 128;
+
+/// Bounds on the resources a rule may use, to apply rules from untrusted sources. Exceeding a
+/// limit aborts the evaluation with `Error::LimitExceeded`, whose `limit` is the name of the field.
+/// Only the depth is limited by default, to `DEFAULT_MAX_DEPTH`.
+///
+/// # Example
+///
+/// ```
+/// use jsonlogic::limits::Limits;
+/// use jsonlogic::{Error, Evaluator};
+/// use serde_json::json;
+///
+/// let mut evaluator = Evaluator::new();
+/// evaluator.set_limits(Limits {
+///     max_size: Some(3),
+///     ..Default::default()
+/// });
+///
+/// let rule = json!({ "merge": [{ "var": "a" }, { "var": "b" }] });
+/// assert_eq!(evaluator.apply(&rule, &json!({ "a": [1], "b": [2] })), Ok(json!([1, 2])));
+/// assert_eq!(
+///     evaluator.apply(&rule, &json!({ "a": [1, 2], "b": [3, 4] })),
+///     Err(Error::LimitExceeded {
+///         limit: String::from("max_size"),
+///         path: String::from("$"),
+///     })
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Limits {
+    /// The maximum number of operations evaluated when the rule is applied once. Operations that
+    /// are applied to each array element, like the logic of `map`, count once per element.
+    pub max_steps: Option<usize>,
+    /// The maximum nesting of JSON objects and arrays in the rule. Checked when the rule is
+    /// compiled, so that deep rules are rejected before they are parsed recursively.
+    pub max_depth: Option<usize>,
+    /// The maximum length of an array, or of a string in bytes, computed by an operation. Values
+    /// an operation only passes on, like the data read by `var` or the branch returned by `if`,
+    /// are not limited.
+    pub max_size: Option<usize>,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            max_steps: None,
+            max_depth: Some(DEFAULT_MAX_DEPTH),
+            max_size: None,
+        }
+    }
+}
+
+impl Limits {
+    /// Errors if the given rule is nested deeper than the depth limit. Walks the JSON without
+    /// recursion, so that the check itself cannot overflow the stack.
+    pub(crate) fn check_depth(&self, json: &Value) -> Result<(), Error> {
+        let max_depth = match self.max_depth {
+            Some(max_depth) => max_depth,
+            None => return Ok(()),
+        };
+
+        let mut pending = vec![(json, 0, String::from(ROOT_PATH))];
+        while let Some((value, depth, path)) = pending.pop() {
+            let children: Vec<(&Value, String)> = match value {
+                Value::Array(items) => items
+                    .iter()
+                    .enumerate()
+                    .map(|(i, item)| (item, index_path(&path, i)))
+                    .collect(),
+                Value::Object(object) => object
+                    .iter()
+                    .map(|(key, item)| (item, key_path(&path, key)))
+                    .collect(),
+                _ => continue,
+            };
+            if depth == max_depth && !children.is_empty() {
+                return Err(limit_exceeded("max_depth", &path));
+            }
+            pending.extend(
+                children
+                    .into_iter()
+                    .map(|(item, path)| (item, depth + 1, path)),
+            );
+        }
+        Ok(())
+    }
+
+    /// Returns whether the given array or string is larger than the size limit.
+    pub(crate) fn exceeds_size(&self, value: &Value) -> bool {
+        let max_size = match self.max_size {
+            Some(max_size) => max_size,
+            None => return false,
+        };
+        match value {
+            Value::Array(items) => items.len() > max_size,
+            Value::String(s) => s.len() > max_size,
+            _ => false,
+        }
+    }
+}
+
+pub(crate) fn limit_exceeded(limit: &str, path: &str) -> Error {
+    Error::LimitExceeded {
+        limit: limit.to_owned(),
+        path: path.to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn depth() {
+        let limits = Limits {
+            max_depth: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(limits.check_depth(&json!(1)), Ok(()));
+        assert_eq!(limits.check_depth(&json!({ "and": [true, []] })), Ok(()));
+        assert_eq!(
+            limits.check_depth(&json!({ "and": [true, { "!": true }] })),
+            Err(limit_exceeded("max_depth", "$.and[1]"))
+        );
+        assert_eq!(Limits::default().check_depth(&json!([[[[[[1]]]]]])), Ok(()));
+
+        let unlimited = Limits {
+            max_depth: None,
+            ..Default::default()
+        };
+        let mut deep = json!(1);
+        for _ in 0..DEFAULT_MAX_DEPTH + 1 {
+            deep = json!([deep]);
+        }
+        assert_eq!(unlimited.check_depth(&deep), Ok(()));
+        assert!(Limits::default().check_depth(&deep).is_err());
+    }
+
+    #[test]
+    fn size() {
+        let limits = Limits {
+            max_size: Some(2),
+            ..Default::default()
+        };
+        assert!(!limits.exceeds_size(&json!([1, 2])));
+        assert!(limits.exceeds_size(&json!([1, 2, 3])));
+        assert!(limits.exceeds_size(&json!("abc")));
+        assert!(!limits.exceeds_size(&json!(12345)));
+        assert!(!Limits::default().exceeds_size(&json!("abc")));
+    }
+}
@@ -0,0 +1,94 @@
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+
+/// Receives the values logged by the `log` operator.
+///
+/// Closures taking the value and the JSON path of the `log` operation implement this trait, so
+/// the output can be forwarded to any logging framework:
+///
+/// ```
+/// use jsonlogic::Evaluator;
+/// use serde_json::{json, Value};
+///
+/// let mut evaluator = Evaluator::new();
+/// evaluator.set_log_sink(|value: &Value, path: &str| eprintln!("{}: {}", path, value));
+///
+/// let result = evaluator.apply(&json!({ "log": { "var": "a" } }), &json!({ "a": 1 }));
+/// assert_eq!(result, Ok(json!(1)));
+/// ```
+pub trait LogSink: Send + Sync {
+    /// Called with the logged value and the JSONPath of the `log` operation in the rule.
+    fn log(&self, value: &Value, path: &str);
+}
+
+impl<F> LogSink for F
+where
+    F: Fn(&Value, &str) + Send + Sync,
+{
+    fn log(&self, value: &Value, path: &str) {
+        self(value, path)
+    }
+}
+
+/// Prints logged values to stdout. This is the default sink.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StdoutSink;
+
+impl LogSink for StdoutSink {
+    fn log(&self, value: &Value, _path: &str) {
+        println!("{}", value);
+    }
+}
+
+/// Discards logged values.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DisabledSink;
+
+impl LogSink for DisabledSink {
+    fn log(&self, _value: &Value, _path: &str) {}
+}
+
+/// Stores logged values together with the path of the `log` operation, e.g. to check them in
+/// tests. Clones share the stored values.
+///
+/// ```
+/// use jsonlogic::log_sink::CapturingSink;
+/// use jsonlogic::Evaluator;
+/// use serde_json::json;
+///
+/// let sink = CapturingSink::new();
+/// let mut evaluator = Evaluator::new();
+/// evaluator.set_log_sink(sink.clone());
+///
+/// evaluator.apply(&json!({ "+": [{ "log": 1 }, 2] }), &json!(null)).unwrap();
+/// assert_eq!(sink.entries(), vec![(json!(1), String::from("$['+'][0]"))]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CapturingSink {
+    entries: Arc<Mutex<Vec<(Value, String)>>>,
+}
+
+impl CapturingSink {
+    pub fn new() -> CapturingSink {
+        CapturingSink::default()
+    }
+
+    /// Returns the logged values and paths in the order they were logged.
+    pub fn entries(&self) -> Vec<(Value, String)> {
+        self.entries.lock().unwrap().clone()
+    }
+
+    /// Removes all stored values.
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
+
+impl LogSink for CapturingSink {
+    fn log(&self, value: &Value, path: &str) {
+        self.entries
+            .lock()
+            .unwrap()
+            .push((value.clone(), path.to_owned()));
+    }
+}
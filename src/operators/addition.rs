@@ -1,39 +1,50 @@
-use serde_json::{Number, Value};
+use serde_json::Value;
 
-use super::{logic, Data, Expression, PartialResult};
+use super::integer::{self, Numeric};
+use super::{Data, Expression, Operator, PartialResult};
 
 /// +, takes an arbitrary number of arguments and sums them up. If just one argument is passed, it
 /// will be cast to a number. Returns `Value::Null` if one argument cannot be coerced into a
-/// number.
+/// number, or if the sum is not finite.
 pub fn compute(args: &[Expression], data: &Data) -> Value {
-    let mut result = 0f64;
+    let mode = integer::mode(data);
+    let mut result = Numeric::new(0, mode);
 
-    for arg in args.iter() {
+    for (i, arg) in args.iter().enumerate() {
         // Use parseFloat like in the javascript implementation.
         // parseFloat(null) is NaN, whereas coerce_to_f64 would return 0.
-        match logic::parse_float(&arg.compute(data)) {
-            Some(num) => result += num,
+        let num = match Numeric::parse_float(&arg.compute(&data.argument(i)), mode) {
+            Some(num) => num,
             None => return Value::Null,
-        }
+        };
+        result = match result.checked_add(num) {
+            Some(result) => result,
+            None => return integer::overflow(Operator::Addition, data),
+        };
     }
 
-    Value::Number(Number::from_f64(result).unwrap())
+    result.into_value()
 }
 
 // early returns on finding any Ambiguous arg
 pub fn partial_compute(args: &[Expression], data: &Data) -> PartialResult {
-    let mut result = 0f64;
+    let mode = integer::mode(data);
+    let mut result = Numeric::new(0, mode);
 
-    for arg in args.iter() {
+    for (i, arg) in args.iter().enumerate() {
         // Use parseFloat like in the javascript implementation.
         // parseFloat(null) is NaN, whereas coerce_to_f64 would return 0.
-        match logic::parse_float(&arg.partial_compute(data)?) {
-            Some(num) => result += num,
+        let num = match Numeric::parse_float(&arg.partial_compute(&data.argument(i))?, mode) {
+            Some(num) => num,
             None => return Ok(Value::Null),
-        }
+        };
+        result = match result.checked_add(num) {
+            Some(result) => result,
+            None => return Ok(integer::overflow(Operator::Addition, data)),
+        };
     }
 
-    Ok(Value::Number(Number::from_f64(result).unwrap()))
+    Ok(result.into_value())
 }
 
 #[cfg(test)]
@@ -53,5 +64,7 @@ mod tests {
             compute_const!(json!(4), json!(2), json!(2), json!(2)),
             json!(10.0)
         );
+        assert_eq!(compute_const!(json!("1e308"), json!("1e308")), Value::Null);
+        assert_eq!(compute_const!(json!(1e308), json!(-1e308)), json!(0.0));
     }
 }
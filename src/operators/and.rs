@@ -1,11 +1,17 @@
 use serde_json::Value;
 
-use super::{logic, Ambiguous, Data, Expression, PartialResult};
+use super::{
+    logic, resolved_arg, Ambiguous, Data, Expression, Operator, PartialResult, Residual,
+    ResidualResult,
+};
 
 /// Takes an arbitrary number of arguments. Returns the first falsy argument or the last
 /// argument.
 pub fn compute(args: &[Expression], data: &Data) -> Value {
-    let args = args.iter().map(|arg| arg.compute(data));
+    let args = args
+        .iter()
+        .enumerate()
+        .map(|(i, arg)| arg.compute(&data.argument(i)));
     let mut last = None;
 
     for arg in args {
@@ -21,7 +27,10 @@ pub fn compute(args: &[Expression], data: &Data) -> Value {
 
 // for Ambiguous results, returns Ambiguous when no there are no false results
 pub fn partial_compute(args: &[Expression], data: &Data) -> PartialResult {
-    let args = args.iter().map(|arg| arg.partial_compute(data));
+    let args = args
+        .iter()
+        .enumerate()
+        .map(|(i, arg)| arg.partial_compute(&data.argument(i)));
     let mut last = None;
 
     let mut is_ambiguous = false;
@@ -44,3 +53,36 @@ pub fn partial_compute(args: &[Expression], data: &Data) -> PartialResult {
 
     Ok(last.unwrap_or(Value::Null))
 }
+
+// returns the first resolved falsy argument if all arguments before are resolved, otherwise
+// drops the resolved truthy arguments from the residual. A falsy argument after a residual ends the
+// residual, and the last argument is always kept since its value is the result.
+pub fn residual_compute(args: &[Expression], data: &Data) -> ResidualResult {
+    let mut residual_args = vec![];
+    let mut last = None;
+
+    for (index, arg) in args.iter().enumerate() {
+        match arg.residual_compute(&data.argument(index)) {
+            Err(Residual(expr)) => residual_args.push(expr),
+            Ok(value) => {
+                let decisive = !logic::is_truthy(&value);
+                if residual_args.is_empty() {
+                    if decisive {
+                        return Ok(value);
+                    }
+                    last = Some(value);
+                } else if decisive || index == args.len() - 1 {
+                    // The earlier residuals may decide the result first.
+                    residual_args.push(resolved_arg(value, arg));
+                    break;
+                }
+            }
+        }
+    }
+
+    match residual_args.len() {
+        0 => Ok(last.unwrap_or(Value::Null)),
+        1 => Err(Residual(residual_args.pop().unwrap())),
+        _ => Err(Residual(Expression::Computed(Operator::And, residual_args))),
+    }
+}
@@ -5,8 +5,8 @@ use super::{logic, Data, Expression, PartialResult};
 pub fn compute(args: &[Expression], data: &Data) -> Value {
     let mut result = String::new();
 
-    for arg in args {
-        let val = arg.compute(data);
+    for (i, arg) in args.iter().enumerate() {
+        let val = arg.compute(&data.argument(i));
         result.push_str(&logic::coerce_to_str(&val));
     }
 
@@ -17,8 +17,8 @@ pub fn compute(args: &[Expression], data: &Data) -> Value {
 pub fn partial_compute(args: &[Expression], data: &Data) -> PartialResult {
     let mut result = String::new();
 
-    for arg in args {
-        let val = arg.partial_compute(data)?;
+    for (i, arg) in args.iter().enumerate() {
+        let val = arg.partial_compute(&data.argument(i))?;
         result.push_str(&logic::coerce_to_str(&val));
     }
 
@@ -0,0 +1,112 @@
+use rust_decimal::Decimal;
+use serde_json::{Number, Value};
+
+/// Returns the exact decimal the value represents: a JSON number, or a string of a number like
+/// `"12.50"` or `"1e-3"`. Returns `None` if the number cannot be represented as a decimal.
+pub fn from_value(value: &Value) -> Option<Decimal> {
+    match value {
+        Value::Number(num) => from_number(num),
+        Value::String(s) if is_number_literal(s.trim()) => parse_decimal(s.trim()),
+        _ => None,
+    }
+}
+
+/// Returns the exact decimal of the JSON number, see `from_value`.
+pub fn from_number(num: &Number) -> Option<Decimal> {
+    parse_decimal(&num.to_string())
+}
+
+/// Returns the exact sum, or `None` if it overflows or has to be rounded.
+pub fn add(a: Decimal, b: Decimal) -> Option<Decimal> {
+    a.checked_add(b)
+        .filter(|sum| sum.scale() == a.scale().max(b.scale()))
+}
+
+/// Returns the exact difference, or `None` if it overflows or has to be rounded.
+pub fn sub(a: Decimal, b: Decimal) -> Option<Decimal> {
+    a.checked_sub(b)
+        .filter(|difference| difference.scale() == a.scale().max(b.scale()))
+}
+
+/// Returns the exact product, or `None` if it overflows or has to be rounded, e.g. because it has
+/// more than 28 fractional digits like `1e-20 * 1e-20`.
+pub fn mul(a: Decimal, b: Decimal) -> Option<Decimal> {
+    let (a, b) = (a.normalize(), b.normalize());
+    a.checked_mul(b)
+        .filter(|product| product.scale() == a.scale() + b.scale())
+}
+
+/// Returns the exact quotient, or `None` if the divisor is 0, or if the quotient overflows or has
+/// to be rounded like `1 / 3`.
+pub fn div(a: Decimal, b: Decimal) -> Option<Decimal> {
+    a.checked_div(b)
+        .filter(|&quotient| mul(quotient, b) == Some(a))
+}
+
+fn parse_decimal(literal: &str) -> Option<Decimal> {
+    if literal.contains(['e', 'E']) {
+        Decimal::from_scientific(literal).ok()
+    } else {
+        Decimal::from_str_exact(literal).ok()
+    }
+}
+
+/// Returns whether the string is a decimal number with an optional sign and exponent.
+fn is_number_literal(s: &str) -> bool {
+    let s = s.strip_prefix(['+', '-']).unwrap_or(s);
+    let (mantissa, exponent) = match s.find(['e', 'E']) {
+        Some(index) => (&s[..index], Some(&s[index + 1..])),
+        None => (s, None),
+    };
+
+    let mut parts = mantissa.splitn(2, '.');
+    let integer_part = parts.next().unwrap_or("");
+    let fraction = parts.next().unwrap_or("");
+    let is_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    let valid_mantissa = is_digits(integer_part)
+        && is_digits(fraction)
+        && !(integer_part.is_empty() && fraction.is_empty());
+
+    let valid_exponent = match exponent {
+        Some(exponent) => {
+            let digits = exponent.strip_prefix(['+', '-']).unwrap_or(exponent);
+            !digits.is_empty() && is_digits(digits)
+        }
+        None => true,
+    };
+
+    valid_mantissa && valid_exponent
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exact_operations() {
+        let dec = |s: &str| parse_decimal(s).unwrap();
+        assert_eq!(add(dec("0.1"), dec("0.2")), Some(dec("0.3")));
+        assert_eq!(add(dec("1e20"), dec("1e-20")), None);
+        assert_eq!(sub(dec("1e20"), dec("1e-20")), None);
+        assert_eq!(mul(dec("0.5"), dec("0.2")), Some(dec("0.1")));
+        assert_eq!(
+            mul(dec("1.000000000000000000000"), dec("1.5")),
+            Some(dec("1.5"))
+        );
+        assert_eq!(mul(dec("1e-20"), dec("1e-20")), None);
+        assert_eq!(div(dec("1.5"), dec("2")), Some(dec("0.75")));
+        assert_eq!(div(dec("1"), dec("3")), None);
+        assert_eq!(div(dec("1e-20"), dec("1e20")), None);
+        assert_eq!(div(dec("1"), dec("0")), None);
+    }
+
+    #[test]
+    fn number_literal() {
+        assert!(is_number_literal("-.5e+3"));
+        assert!(is_number_literal("5."));
+        assert!(!is_number_literal("."));
+        assert!(!is_number_literal("1_000"));
+        assert!(!is_number_literal("1e"));
+        assert!(!is_number_literal("inf"));
+    }
+}
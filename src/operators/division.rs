@@ -1,14 +1,16 @@
-use serde_json::{Number, Value};
+use serde_json::Value;
 
-use super::{logic, Data, Expression, PartialResult};
+use super::integer::{self, Numeric};
+use super::{Data, Expression, PartialResult};
 
 /// "/", takes two arguments that are coerced into numbers. Returns `Value::Null` if the divisor is
 /// coerced to `0` or one argument cannot be coerced into a number.
 pub fn compute(args: &[Expression], data: &Data) -> Value {
+    let mode = integer::mode(data);
     let a = match args
-        .get(0)
-        .map(|arg| arg.compute(data))
-        .and_then(|a| logic::coerce_to_f64(&a))
+        .first()
+        .map(|arg| arg.compute(&data.argument(0)))
+        .and_then(|a| Numeric::coerce(&a, mode))
     {
         Some(a) => a,
         None => return Value::Null,
@@ -16,26 +18,24 @@ pub fn compute(args: &[Expression], data: &Data) -> Value {
 
     let b = match args
         .get(1)
-        .map(|arg| arg.compute(data))
-        .and_then(|b| logic::coerce_to_f64(&b))
+        .map(|arg| arg.compute(&data.argument(1)))
+        .and_then(|b| Numeric::coerce(&b, mode))
     {
         Some(b) => b,
         None => return Value::Null,
     };
 
-    match Number::from_f64(a / b) {
-        Some(num) => Value::Number(num),
-        None => Value::Null,
-    }
+    a.div(b).into_value()
 }
 
 // early returns on finding either Ambiguous arg
 pub fn partial_compute(args: &[Expression], data: &Data) -> PartialResult {
+    let mode = integer::mode(data);
     let a = match args
-        .get(0)
-        .map(|arg| arg.partial_compute(data))
+        .first()
+        .map(|arg| arg.partial_compute(&data.argument(0)))
         .transpose()?
-        .and_then(|a| logic::coerce_to_f64(&a))
+        .and_then(|a| Numeric::coerce(&a, mode))
     {
         Some(a) => a,
         None => return Ok(Value::Null),
@@ -43,18 +43,15 @@ pub fn partial_compute(args: &[Expression], data: &Data) -> PartialResult {
 
     let b = match args
         .get(1)
-        .map(|arg| arg.partial_compute(data))
+        .map(|arg| arg.partial_compute(&data.argument(1)))
         .transpose()?
-        .and_then(|b| logic::coerce_to_f64(&b))
+        .and_then(|b| Numeric::coerce(&b, mode))
     {
         Some(b) => b,
         None => return Ok(Value::Null),
     };
 
-    match Number::from_f64(a / b) {
-        Some(num) => Ok(Value::Number(num)),
-        None => Ok(Value::Null),
-    }
+    Ok(a.div(b).into_value())
 }
 
 #[cfg(test)]
@@ -1,16 +1,17 @@
 use serde_json::Value;
+use std::borrow::Cow;
 
 use super::{logic, Data, Expression, PartialResult};
 
 pub fn compute(args: &[Expression], data: &Data) -> Value {
     let a = args
-        .get(0)
-        .map(|arg| arg.compute(data))
-        .unwrap_or(Value::Null);
+        .first()
+        .map(|arg| arg.compute_borrowed(&data.argument(0)))
+        .unwrap_or(Cow::Owned(Value::Null));
     let b = args
         .get(1)
-        .map(|arg| arg.compute(data))
-        .unwrap_or(Value::Null);
+        .map(|arg| arg.compute_borrowed(&data.argument(1)))
+        .unwrap_or(Cow::Owned(Value::Null));
 
     Value::Bool(logic::is_abstract_equal(&a, &b))
 }
@@ -18,12 +19,12 @@ pub fn compute(args: &[Expression], data: &Data) -> Value {
 // early returns on finding either Ambiguous arg
 pub fn partial_compute(args: &[Expression], data: &Data) -> PartialResult {
     let a = args
-        .get(0)
-        .map(|arg| arg.partial_compute(data))
+        .first()
+        .map(|arg| arg.partial_compute(&data.argument(0)))
         .unwrap_or(Ok(Value::Null))?;
     let b = args
         .get(1)
-        .map(|arg| arg.partial_compute(data))
+        .map(|arg| arg.partial_compute(&data.argument(1)))
         .unwrap_or(Ok(Value::Null))?;
 
     Ok(Value::Bool(logic::is_abstract_equal(&a, &b)))
@@ -4,24 +4,27 @@ use super::{logic, Ambiguous, Data, Expression, PartialResult};
 
 /// You can use `filter` to keep only elements of the array that pass a test. Note, that inside
 /// the logic being used to map, var operations are relative to the array element being worked
-/// on.
+/// on, with access to the enclosing data and the index like in `map`.
 ///
 /// Also note, the returned array will have contiguous indexes starting at zero (typical for
 /// JavaScript, Python and Ruby) it will not preserve the source indexes (making it unlike
 /// PHP’s array_filter).
 pub fn compute(args: &[Expression], data: &Data) -> Value {
-    let arr = match args.get(0).map(|arg| arg.compute(data)) {
-        Some(Value::Array(arr)) => arr,
-        _ => Vec::with_capacity(0),
+    let value = args
+        .first()
+        .map(|arg| arg.compute_borrowed(&data.argument(0)));
+    let arr = match value.as_deref() {
+        Some(Value::Array(arr)) => arr.as_slice(),
+        _ => &[],
     };
     let op = match args.get(1) {
         Some(expr) => expr,
-        None => &Expression::Constant(&Value::Null),
+        None => &Expression::Constant(Value::Null),
     };
 
     let mut result = Vec::new();
-    for elem in arr.iter() {
-        let include = op.compute(&Data::from_json(elem));
+    for (index, elem) in arr.iter().enumerate() {
+        let include = op.compute(&data.argument(1).scoped_element(elem, index));
         if logic::is_truthy(&include) {
             result.push(elem.clone());
         }
@@ -33,8 +36,8 @@ pub fn compute(args: &[Expression], data: &Data) -> Value {
 // early returns on finding Ambiguous first arg and for Ambiguous results, returns Ambiguous when no there are no false results
 pub fn partial_compute(args: &[Expression], data: &Data) -> PartialResult {
     let arr = match args
-        .get(0)
-        .map(|arg| arg.partial_compute(data))
+        .first()
+        .map(|arg| arg.partial_compute(&data.argument(0)))
         .transpose()?
     {
         Some(Value::Array(arr)) => arr,
@@ -42,14 +45,14 @@ pub fn partial_compute(args: &[Expression], data: &Data) -> PartialResult {
     };
     let op = match args.get(1) {
         Some(expr) => expr,
-        None => &Expression::Constant(&Value::Null),
+        None => &Expression::Constant(Value::Null),
     };
 
     let mut is_ambiguous = false;
 
     let mut result = Vec::new();
-    for elem in arr.iter() {
-        match op.partial_compute(&Data::from_json(elem)) {
+    for (index, elem) in arr.iter().enumerate() {
+        match op.partial_compute(&data.argument(1).scoped_element(elem, index)) {
             Err(Ambiguous) => is_ambiguous = true,
             Ok(include) if logic::is_truthy(&include) => result.push(elem.clone()),
             _ => (),
@@ -3,13 +3,13 @@ use serde_json::Value;
 use super::{logic, Data, Expression, PartialResult};
 
 pub fn compute(args: &[Expression], data: &Data) -> Value {
-    let a = match args.get(0) {
-        Some(arg) => arg.compute(data),
+    let a = match args.first() {
+        Some(arg) => arg.compute_borrowed(&data.argument(0)),
         None => return Value::Bool(false),
     };
 
     let b = match args.get(1) {
-        Some(arg) => arg.compute(data),
+        Some(arg) => arg.compute_borrowed(&data.argument(1)),
         None => return Value::Bool(false),
     };
 
@@ -18,13 +18,13 @@ pub fn compute(args: &[Expression], data: &Data) -> Value {
 
 // early returns on finding either Ambiguous arg
 pub fn partial_compute(args: &[Expression], data: &Data) -> PartialResult {
-    let a = match args.get(0) {
-        Some(arg) => arg.partial_compute(data)?,
+    let a = match args.first() {
+        Some(arg) => arg.partial_compute(&data.argument(0))?,
         None => return Ok(Value::Bool(false)),
     };
 
     let b = match args.get(1) {
-        Some(arg) => arg.partial_compute(data)?,
+        Some(arg) => arg.partial_compute(&data.argument(1))?,
         None => return Ok(Value::Bool(false)),
     };
 
@@ -1,25 +1,30 @@
 use serde_json::Value;
+use std::borrow::Cow;
 
-use super::{logic, Data, Expression, PartialResult};
+use super::{
+    logic, residual_arg, Data, Expression, Operator, PartialResult, Residual, ResidualResult,
+};
 
 pub fn compute(args: &[Expression], data: &Data) -> Value {
     match args.len() {
         // Return the condition, for whatever reason.
         0..=1 => args
-            .get(0)
-            .map(|arg| arg.compute(data))
+            .first()
+            .map(|arg| arg.compute(&data.argument(0)))
             .unwrap_or(Value::Null),
         // Normal if/then/else, with default null.
         2..=3 => {
             let condition = args
-                .get(0)
-                .map(|arg| arg.compute(data))
-                .unwrap_or(Value::Null);
+                .first()
+                .map(|arg| arg.compute_borrowed(&data.argument(0)))
+                .unwrap_or(Cow::Owned(Value::Null));
             if logic::is_truthy(&condition) {
-                args.get(1).map(|arg| arg.compute(data)).unwrap()
+                args.get(1)
+                    .map(|arg| arg.compute(&data.argument(1)))
+                    .unwrap()
             } else {
                 args.get(2)
-                    .map(|arg| arg.compute(data))
+                    .map(|arg| arg.compute(&data.argument(2)))
                     .unwrap_or(Value::Null)
             }
         }
@@ -28,12 +33,12 @@ pub fn compute(args: &[Expression], data: &Data) -> Value {
         // TODO: Actually the logic of this arm computes the other cases properly. Test whether
         // the short circuit cases have a performance benefit or not.
         _ => {
-            let mut args = args.iter();
+            let mut args = args.iter().enumerate();
 
             loop {
                 let condition_or_else_val = args
                     .next()
-                    .map(|arg| arg.compute(data))
+                    .map(|(i, arg)| arg.compute(&data.argument(i)))
                     .unwrap_or(Value::Null);
                 let then_val = args.next();
 
@@ -44,9 +49,9 @@ pub fn compute(args: &[Expression], data: &Data) -> Value {
                     None => return condition_or_else_val,
                     // If the condition (arg1) is truthy, return the then value (arg2).
                     // Otherwise just continue with the next pair.
-                    Some(then_val) => {
+                    Some((i, then_val)) => {
                         if logic::is_truthy(&condition_or_else_val) {
-                            return then_val.compute(data);
+                            return then_val.compute(&data.argument(i));
                         }
                     }
                 }
@@ -60,20 +65,22 @@ pub fn partial_compute(args: &[Expression], data: &Data) -> PartialResult {
     match args.len() {
         // Return the condition, for whatever reason.
         0..=1 => args
-            .get(0)
-            .map(|arg| arg.partial_compute(data))
+            .first()
+            .map(|arg| arg.partial_compute(&data.argument(0)))
             .unwrap_or(Ok(Value::Null)),
         // Normal if/then/else, with default null.
         2..=3 => {
             let condition = args
-                .get(0)
-                .map(|arg| arg.partial_compute(data))
+                .first()
+                .map(|arg| arg.partial_compute(&data.argument(0)))
                 .unwrap_or(Ok(Value::Null))?;
             if logic::is_truthy(&condition) {
-                args.get(1).map(|arg| arg.partial_compute(data)).unwrap()
+                args.get(1)
+                    .map(|arg| arg.partial_compute(&data.argument(1)))
+                    .unwrap()
             } else {
                 args.get(2)
-                    .map(|arg| arg.partial_compute(data))
+                    .map(|arg| arg.partial_compute(&data.argument(2)))
                     .unwrap_or(Ok(Value::Null))
             }
         }
@@ -82,12 +89,12 @@ pub fn partial_compute(args: &[Expression], data: &Data) -> PartialResult {
         // TODO: Actually the logic of this arm computes the other cases properly. Test whether
         // the short circuit cases have a performance benefit or not.
         _ => {
-            let mut args = args.iter();
+            let mut args = args.iter().enumerate();
 
             loop {
                 let condition_or_else_val = args
                     .next()
-                    .map(|arg| arg.partial_compute(data))
+                    .map(|(i, arg)| arg.partial_compute(&data.argument(i)))
                     .unwrap_or(Ok(Value::Null));
                 let then_val = args.next();
 
@@ -98,9 +105,9 @@ pub fn partial_compute(args: &[Expression], data: &Data) -> PartialResult {
                     None => return condition_or_else_val,
                     // If the condition (arg1) is truthy, return the then value (arg2).
                     // Otherwise just continue with the next pair.
-                    Some(then_val) => {
+                    Some((i, then_val)) => {
                         if logic::is_truthy(&condition_or_else_val?) {
-                            return then_val.partial_compute(data);
+                            return then_val.partial_compute(&data.argument(i));
                         }
                     }
                 }
@@ -109,6 +116,60 @@ pub fn partial_compute(args: &[Expression], data: &Data) -> PartialResult {
     }
 }
 
+// drops the condition/then pairs whose condition resolves to a falsy value. A condition that
+// resolves to a truthy value ends the chain, its then value becomes the else value of the residual.
+pub fn residual_compute(args: &[Expression], data: &Data) -> ResidualResult {
+    if args.len() < 2 {
+        return args
+            .first()
+            .map(|arg| arg.residual_compute(&data.argument(0)))
+            .unwrap_or(Ok(Value::Null));
+    }
+
+    let mut residual_args = vec![];
+    let mut args = args.iter().enumerate();
+
+    loop {
+        let condition_or_else = args.next();
+        let then_arg = args.next();
+
+        let ((i, condition), (j, then_arg)) = match (condition_or_else, then_arg) {
+            (Some(condition), Some(then_arg)) => (condition, then_arg),
+            // The else argument, or the implicit null else value if there is none.
+            (else_arg, _) => {
+                match else_arg {
+                    Some((i, else_arg)) if residual_args.is_empty() => {
+                        return else_arg.residual_compute(&data.argument(i))
+                    }
+                    Some((i, else_arg)) => {
+                        residual_args.push(residual_arg(else_arg, &data.argument(i)))
+                    }
+                    None if residual_args.is_empty() => return Ok(Value::Null),
+                    None => (),
+                }
+                break;
+            }
+        };
+
+        match condition.residual_compute(&data.argument(i)) {
+            Ok(value) if logic::is_truthy(&value) => {
+                if residual_args.is_empty() {
+                    return then_arg.residual_compute(&data.argument(j));
+                }
+                residual_args.push(residual_arg(then_arg, &data.argument(j)));
+                break;
+            }
+            Ok(_) => (),
+            Err(Residual(expr)) => {
+                residual_args.push(expr);
+                residual_args.push(residual_arg(then_arg, &data.argument(j)));
+            }
+        }
+    }
+
+    Err(Residual(Expression::Computed(Operator::If, residual_args)))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,352 @@
+#[cfg(feature = "decimal")]
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+use serde_json::{Number, Value};
+
+#[cfg(feature = "decimal")]
+use super::decimal;
+use super::{logic, Data, Operator};
+use crate::error::Error;
+
+/// The range of integers that can be represented as a JSON number without losing precision.
+const MIN: i128 = i64::MIN as i128;
+const MAX: i128 = u64::MAX as i128;
+
+/// How arithmetic operations compute their results.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Arithmetic {
+    /// With floats, like javascript.
+    Float,
+    /// With exact integers if all operands are integers.
+    Integer,
+    /// With exact decimals if all operands are decimal numbers.
+    #[cfg(feature = "decimal")]
+    Decimal,
+}
+
+/// An operand or result of an arithmetic operation. Integers and decimals are only used in the
+/// respective mode, as long as all operands of the operation are exact. Otherwise, like in
+/// javascript, the operation is computed with floats.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Numeric {
+    Integer(i128),
+    Float(f64),
+    #[cfg(feature = "decimal")]
+    Decimal(Decimal),
+}
+
+/// Returns the arithmetic mode of the evaluation the data belongs to.
+pub fn mode(data: &Data) -> Arithmetic {
+    match data.context() {
+        Some(context) => context.settings.arithmetic,
+        None => Arithmetic::Float,
+    }
+}
+
+impl Numeric {
+    /// Returns the given integer in the given mode.
+    pub fn new(num: i64, mode: Arithmetic) -> Numeric {
+        match mode {
+            Arithmetic::Float => Numeric::Float(num as f64),
+            Arithmetic::Integer => Numeric::Integer(i128::from(num)),
+            #[cfg(feature = "decimal")]
+            Arithmetic::Decimal => Numeric::Decimal(Decimal::from(num)),
+        }
+    }
+
+    /// Coerces the value like `logic::parse_float`, but keeps exact numbers in the given mode.
+    pub fn parse_float(value: &Value, mode: Arithmetic) -> Option<Numeric> {
+        match Numeric::exact(value, mode) {
+            Some(num) => Some(num),
+            None => logic::parse_float(value).map(Numeric::Float),
+        }
+    }
+
+    /// Coerces the value like `logic::coerce_to_f64`, but keeps exact numbers in the given mode.
+    pub fn coerce(value: &Value, mode: Arithmetic) -> Option<Numeric> {
+        let exact = match value {
+            Value::Null => Some(Numeric::new(0, mode)),
+            Value::Bool(b) => Some(Numeric::new(i64::from(*b), mode)),
+            _ => Numeric::exact(value, mode),
+        };
+        match exact {
+            Some(Numeric::Float(_)) | None => logic::coerce_to_f64(value).map(Numeric::Float),
+            exact => exact,
+        }
+    }
+
+    fn exact(value: &Value, mode: Arithmetic) -> Option<Numeric> {
+        match mode {
+            Arithmetic::Float => None,
+            Arithmetic::Integer => integer(value).map(Numeric::Integer),
+            #[cfg(feature = "decimal")]
+            Arithmetic::Decimal => decimal::from_value(value).map(Numeric::Decimal),
+        }
+    }
+
+    pub fn to_f64(self) -> f64 {
+        match self {
+            Numeric::Integer(num) => num as f64,
+            Numeric::Float(num) => num,
+            #[cfg(feature = "decimal")]
+            Numeric::Decimal(num) => num.to_f64().unwrap_or(f64::NAN),
+        }
+    }
+
+    /// Returns `None` if the integer result overflows.
+    pub fn checked_add(self, other: Numeric) -> Option<Numeric> {
+        self.checked(
+            other,
+            i128::checked_add,
+            #[cfg(feature = "decimal")]
+            decimal::add,
+            |a, b| a + b,
+        )
+    }
+
+    /// Returns `None` if the integer result overflows.
+    pub fn checked_sub(self, other: Numeric) -> Option<Numeric> {
+        self.checked(
+            other,
+            i128::checked_sub,
+            #[cfg(feature = "decimal")]
+            decimal::sub,
+            |a, b| a - b,
+        )
+    }
+
+    /// Returns `None` if the integer result overflows.
+    pub fn checked_mul(self, other: Numeric) -> Option<Numeric> {
+        self.checked(
+            other,
+            i128::checked_mul,
+            #[cfg(feature = "decimal")]
+            decimal::mul,
+            |a, b| a * b,
+        )
+    }
+
+    /// Returns the quotient. Only decimals are divided exactly, integers and quotients that are no
+    /// exact decimal are divided as floats.
+    pub fn div(self, other: Numeric) -> Numeric {
+        match (self, other) {
+            #[cfg(feature = "decimal")]
+            (Numeric::Decimal(a), Numeric::Decimal(b)) => match decimal::div(a, b) {
+                Some(num) => Numeric::Decimal(num),
+                None => Numeric::Float(self.to_f64() / other.to_f64()),
+            },
+            (a, b) => Numeric::Float(a.to_f64() / b.to_f64()),
+        }
+    }
+
+    /// Returns the remainder, which is NaN for a divisor of 0 like in javascript.
+    pub fn rem(self, other: Numeric) -> Numeric {
+        match (self, other) {
+            (Numeric::Integer(a), Numeric::Integer(b)) if b != 0 => Numeric::Integer(a % b),
+            #[cfg(feature = "decimal")]
+            (Numeric::Decimal(a), Numeric::Decimal(b)) if !b.is_zero() => match a.checked_rem(b) {
+                Some(num) => Numeric::Decimal(num),
+                None => Numeric::Float(self.to_f64() % other.to_f64()),
+            },
+            (a, b) => Numeric::Float(a.to_f64() % b.to_f64()),
+        }
+    }
+
+    /// Returns `None` if the integer result overflows.
+    pub fn checked_neg(self) -> Option<Numeric> {
+        match self {
+            Numeric::Integer(num) => Some(num)
+                .filter(|num| -num >= MIN)
+                .map(|num| Numeric::Integer(-num)),
+            Numeric::Float(num) => Some(Numeric::Float(-num)),
+            #[cfg(feature = "decimal")]
+            Numeric::Decimal(num) => Some(Numeric::Decimal(-num)),
+        }
+    }
+
+    /// Compares integers and decimals exactly, and all other numbers as floats.
+    pub fn less_than(self, other: Numeric) -> bool {
+        match (self, other) {
+            (Numeric::Integer(a), Numeric::Integer(b)) => a < b,
+            #[cfg(feature = "decimal")]
+            (Numeric::Decimal(a), Numeric::Decimal(b)) => a < b,
+            (a, b) => a.to_f64() < b.to_f64(),
+        }
+    }
+
+    pub fn into_value(self) -> Value {
+        match self {
+            // Integers are always within the range of i64 or u64.
+            Numeric::Integer(num) if num < 0 => Value::Number(Number::from(num as i64)),
+            Numeric::Integer(num) => Value::Number(Number::from(num as u64)),
+            Numeric::Float(num) => logic::number_value(num),
+            // The normalized decimal has no trailing zeros, so `0.10 + 0.20` is `0.3`.
+            #[cfg(feature = "decimal")]
+            Numeric::Decimal(num) => {
+                Value::Number(Number::from_string_unchecked(num.normalize().to_string()))
+            }
+        }
+    }
+
+    /// Computes the operation exactly if both operands are exact. Integers that overflow return
+    /// `None`, decimal results that overflow or would be rounded are computed as floats instead.
+    fn checked(
+        self,
+        other: Numeric,
+        integer_op: fn(i128, i128) -> Option<i128>,
+        #[cfg(feature = "decimal")] decimal_op: fn(Decimal, Decimal) -> Option<Decimal>,
+        float_op: fn(f64, f64) -> f64,
+    ) -> Option<Numeric> {
+        match (self, other) {
+            (Numeric::Integer(a), Numeric::Integer(b)) => integer_op(a, b)
+                .filter(|num| (MIN..=MAX).contains(num))
+                .map(Numeric::Integer),
+            #[cfg(feature = "decimal")]
+            (Numeric::Decimal(a), Numeric::Decimal(b)) => match decimal_op(a, b) {
+                Some(num) => Some(Numeric::Decimal(num)),
+                None => Some(Numeric::Float(float_op(self.to_f64(), other.to_f64()))),
+            },
+            (a, b) => Some(Numeric::Float(float_op(a.to_f64(), b.to_f64()))),
+        }
+    }
+}
+
+/// Returns the integer the value represents: an integer JSON number, or a string of an integer.
+fn integer(value: &Value) -> Option<i128> {
+    match value {
+        Value::Number(num) => match num.as_i64() {
+            Some(num) => Some(i128::from(num)),
+            None => num.as_u64().map(i128::from),
+        },
+        Value::String(s) => {
+            let s = s.trim();
+            let digits = s.strip_prefix(|c| c == '+' || c == '-').unwrap_or(s);
+            if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            s.parse::<i128>()
+                .ok()
+                .filter(|num| (MIN..=MAX).contains(num))
+        }
+        _ => None,
+    }
+}
+
+/// Aborts the evaluation because the integer result of the current operation overflowed. Returns
+/// `Value::Null`, which is the result if there is no evaluation to abort.
+pub fn overflow(operator: Operator, data: &Data) -> Value {
+    if let Some(context) = data.context() {
+        context.fail(Error::IntegerOverflow {
+            operator: operator.as_str().to_owned(),
+            path: context.path(),
+        });
+    }
+    Value::Null
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn coercion() {
+        let integers = Arithmetic::Integer;
+        let floats = Arithmetic::Float;
+        assert_eq!(
+            Numeric::parse_float(&json!(3), integers),
+            Some(Numeric::Integer(3))
+        );
+        assert_eq!(
+            Numeric::parse_float(&json!(3), floats),
+            Some(Numeric::Float(3.0))
+        );
+        assert_eq!(
+            Numeric::parse_float(&json!(" -12 "), integers),
+            Some(Numeric::Integer(-12))
+        );
+        assert_eq!(
+            Numeric::parse_float(&json!("12px"), integers),
+            Some(Numeric::Float(12.0))
+        );
+        assert_eq!(Numeric::parse_float(&json!(null), integers), None);
+        assert_eq!(
+            Numeric::coerce(&json!(null), integers),
+            Some(Numeric::Integer(0))
+        );
+        assert_eq!(
+            Numeric::coerce(&json!(null), floats),
+            Some(Numeric::Float(0.0))
+        );
+        assert_eq!(
+            Numeric::coerce(&json!(1.5), integers),
+            Some(Numeric::Float(1.5))
+        );
+        assert_eq!(
+            Numeric::coerce(&json!(u64::MAX), integers),
+            Some(Numeric::Integer(u64::MAX as i128))
+        );
+    }
+
+    #[test]
+    fn arithmetic() {
+        let int = |num| Numeric::Integer(num);
+        assert_eq!(int(2).checked_add(int(3)), Some(int(5)));
+        assert_eq!(
+            int(2).checked_add(Numeric::Float(0.5)),
+            Some(Numeric::Float(2.5))
+        );
+        assert_eq!(int(MAX).checked_add(int(1)), None);
+        assert_eq!(int(MIN).checked_sub(int(1)), None);
+        assert_eq!(int(MAX).checked_neg(), None);
+        assert_eq!(
+            int(i64::MAX as i128).checked_mul(int(2)),
+            Some(int(2 * i64::MAX as i128))
+        );
+        assert_eq!(int(-7).rem(int(3)), int(-1));
+        assert!(int(1).rem(int(0)).to_f64().is_nan());
+        assert_eq!(int(3).div(int(2)), Numeric::Float(1.5));
+        assert!(int(9_007_199_254_740_992).less_than(int(9_007_199_254_740_993)));
+    }
+
+    #[test]
+    fn into_value() {
+        assert_eq!(Numeric::Integer(3).into_value(), json!(3));
+        assert_eq!(Numeric::Integer(-3).into_value(), json!(-3));
+        assert_eq!(Numeric::Integer(MAX).into_value(), json!(u64::MAX));
+        assert_eq!(Numeric::Float(3.0).into_value(), json!(3.0));
+        assert_eq!(Numeric::Float(f64::NAN).into_value(), Value::Null);
+    }
+
+    #[cfg(feature = "decimal")]
+    #[test]
+    fn decimals() {
+        let dec = |s: &str| Numeric::Decimal(s.parse().unwrap());
+        let decimals = Arithmetic::Decimal;
+
+        assert_eq!(
+            Numeric::parse_float(&json!(0.1), decimals),
+            Some(dec("0.1"))
+        );
+        assert_eq!(
+            Numeric::parse_float(&json!(" 12.50 "), decimals),
+            Some(dec("12.50"))
+        );
+        assert_eq!(
+            Numeric::parse_float(&json!("1e-3"), decimals),
+            Some(dec("0.001"))
+        );
+        assert_eq!(
+            Numeric::parse_float(&json!("12px"), decimals),
+            Some(Numeric::Float(12.0))
+        );
+        assert_eq!(Numeric::coerce(&json!(true), decimals), Some(dec("1")));
+        assert_eq!(
+            dec("0.1").checked_mul(dec("3")).map(Numeric::into_value),
+            Some(json!(0.3))
+        );
+        assert_eq!(dec("1").div(dec("4")), dec("0.25"));
+        assert_eq!(dec("1").div(dec("0")).into_value(), Value::Null);
+        assert_eq!(dec("5.5").rem(dec("2")), dec("1.5"));
+        assert!(dec("0.1").less_than(dec("0.10000000000000000001")));
+    }
+}
@@ -8,12 +8,15 @@ use super::{logic, Data, Expression, PartialResult};
 ///
 /// If the second argument is a string, tests that the first argument is a substring.
 pub fn compute(args: &[Expression], data: &Data) -> Value {
-    let a = match args.get(0) {
-        Some(arg) => arg.compute(data),
+    let a = match args.first() {
+        Some(arg) => arg.compute_borrowed(&data.argument(0)),
         None => return json!(false),
     };
 
-    let result = match args.get(1).map(|arg| arg.compute(data)) {
+    let b = args
+        .get(1)
+        .map(|arg| arg.compute_borrowed(&data.argument(1)));
+    let result = match b.as_deref() {
         // Second argument is an array: test whether the first argument is a member of the array.
         Some(Value::String(b)) => b.contains(&logic::coerce_to_str(&a)),
         // Second argument is a string: test whether the first argument (coerced into a string) is
@@ -26,14 +29,14 @@ pub fn compute(args: &[Expression], data: &Data) -> Value {
 
 // early returns on finding either Ambiguous arg
 pub fn partial_compute(args: &[Expression], data: &Data) -> PartialResult {
-    let a = match args.get(0) {
-        Some(arg) => arg.partial_compute(data)?,
+    let a = match args.first() {
+        Some(arg) => arg.partial_compute(&data.argument(0))?,
         None => return Ok(Value::Bool(false)),
     };
 
     let result = match args
         .get(1)
-        .map(|arg| arg.partial_compute(data))
+        .map(|arg| arg.partial_compute(&data.argument(1)))
         .transpose()?
     {
         // Second argument is an array: test whether the first argument is a member of the array.
@@ -4,13 +4,13 @@ use super::{logic, Data, Expression, PartialResult};
 use serde_json::Value;
 
 pub fn compute(args: &[Expression], data: &Data) -> Value {
-    let first = match args.get(0) {
-        Some(arg) => logic::coerce_to_str(&arg.compute(data)),
+    let first = match args.first() {
+        Some(arg) => logic::coerce_to_str(&arg.compute(&data.argument(0))),
         None => return Value::Bool(false),
     };
 
     let second = match args.get(1) {
-        Some(arg) => logic::coerce_to_str(&arg.compute(data)),
+        Some(arg) => logic::coerce_to_str(&arg.compute(&data.argument(1))),
         None => return Value::Bool(false),
     };
 
@@ -21,13 +21,13 @@ pub fn compute(args: &[Expression], data: &Data) -> Value {
 
 // early returns on finding either Ambiguous arg
 pub fn partial_compute(args: &[Expression], data: &Data) -> PartialResult {
-    let first = match args.get(0) {
-        Some(arg) => logic::coerce_to_str(&arg.partial_compute(data)?),
+    let first = match args.first() {
+        Some(arg) => logic::coerce_to_str(&arg.partial_compute(&data.argument(0))?),
         None => return Ok(Value::Bool(false)),
     };
 
     let second = match args.get(1) {
-        Some(arg) => logic::coerce_to_str(&arg.partial_compute(data)?),
+        Some(arg) => logic::coerce_to_str(&arg.partial_compute(&data.argument(1))?),
         None => return Ok(Value::Bool(false)),
     };
 
@@ -4,20 +4,22 @@ use super::{logic, Ambiguous, Data, Expression, PartialResult};
 use serde_json::Value;
 
 pub fn compute(args: &[Expression], data: &Data) -> Value {
-    let first = match args.get(0) {
-        Some(arg) => logic::coerce_to_str(&arg.compute(data)),
+    let first = match args.first() {
+        Some(arg) => logic::coerce_to_str(&arg.compute(&data.argument(0))),
         None => return Value::Bool(false),
     };
 
     let second = match args.get(1) {
-        Some(arg) => logic::coerce_to_str(&arg.compute(data)),
+        Some(arg) => logic::coerce_to_str(&arg.compute(&data.argument(1))),
         None => return Value::Bool(false),
     };
 
     let third = args
         .get(2)
-        .map(|arg| logic::coerce_to_str(&arg.compute(data)));
+        .map(|arg| logic::coerce_to_str(&arg.compute(&data.argument(2))));
 
+    // `Option::is_none_or` would raise the minimum Rust version to 1.82.
+    #[allow(clippy::unnecessary_map_or)]
     let result = compare_version(&first, &second, true).is_lt()
         && third.map_or(true, |third| compare_version(&second, &third, true).is_lt());
 
@@ -26,13 +28,17 @@ pub fn compute(args: &[Expression], data: &Data) -> Value {
 
 // early returns false on obvious false conditions and returns Ambiguous on other Ambiguous cases
 pub fn partial_compute(args: &[Expression], data: &Data) -> PartialResult {
-    let first = match args.get(0) {
-        Some(arg) => arg.partial_compute(data).map(|a| logic::coerce_to_str(&a)),
+    let first = match args.first() {
+        Some(arg) => arg
+            .partial_compute(&data.argument(0))
+            .map(|a| logic::coerce_to_str(&a)),
         None => return Ok(Value::Bool(false)),
     };
 
     let second = match args.get(1) {
-        Some(arg) => arg.partial_compute(data).map(|b| logic::coerce_to_str(&b)),
+        Some(arg) => arg
+            .partial_compute(&data.argument(1))
+            .map(|b| logic::coerce_to_str(&b)),
         None => return Ok(Value::Bool(false)),
     };
 
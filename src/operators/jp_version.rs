@@ -7,13 +7,12 @@ fn split_version(version: &str) -> VecDeque<String> {
     let mut part = String::new();
     for ch in version.chars() {
         if ch == '.' || ch == '-' {
-            components.push_back(part.drain(..).collect());
-            part = String::new();
+            components.push_back(std::mem::take(&mut part));
         } else {
             part.push(ch);
         }
     }
-    components.push_back(part.drain(..).collect());
+    components.push_back(std::mem::take(&mut part));
     components
 }
 
@@ -48,7 +47,7 @@ pub fn compare_version(str1: &str, str2: &str, alpha_numeric: bool) -> Ordering
         }
     }
 
-    return Ordering::Equal;
+    Ordering::Equal
 }
 
 #[cfg(test)]
@@ -109,7 +108,7 @@ mod tests {
             "2.5.10.4159",
             "10.5",
         ];
-        input.sort_by(|str1: &&str, str2: &&str| compare_version(*str1, *str2, true));
+        input.sort_by(|str1: &&str, str2: &&str| compare_version(str1, str2, true));
 
         assert_eq!(input, expected)
     }
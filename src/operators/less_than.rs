@@ -3,18 +3,18 @@ use serde_json::Value;
 use super::{logic, Ambiguous, Data, Expression, PartialResult};
 
 pub fn compute(args: &[Expression], data: &Data) -> Value {
-    let a = match args.get(0) {
-        Some(arg) => arg.compute(data),
+    let a = match args.first() {
+        Some(arg) => arg.compute_borrowed(&data.argument(0)),
         None => return Value::Bool(false),
     };
 
     let b = match args.get(1) {
-        Some(arg) => arg.compute(data),
+        Some(arg) => arg.compute_borrowed(&data.argument(1)),
         None => return Value::Bool(false),
     };
 
     let result = match args.get(2) {
-        Some(c) => compute_between_exclusive(&a, &b, &c.compute(data)),
+        Some(c) => compute_between_exclusive(&a, &b, &c.compute_borrowed(&data.argument(2))),
         None => compute_less_than(&a, &b),
     };
 
@@ -23,13 +23,13 @@ pub fn compute(args: &[Expression], data: &Data) -> Value {
 
 // early returns false on obvious false conditions and returns Ambiguous on other Ambiguous cases
 pub fn partial_compute(args: &[Expression], data: &Data) -> PartialResult {
-    let a = match args.get(0) {
-        Some(arg) => arg.partial_compute(data),
+    let a = match args.first() {
+        Some(arg) => arg.partial_compute(&data.argument(0)),
         None => return Ok(Value::Bool(false)),
     };
 
     let b = match args.get(1) {
-        Some(arg) => arg.partial_compute(data),
+        Some(arg) => arg.partial_compute(&data.argument(1)),
         None => return Ok(Value::Bool(false)),
     };
 
@@ -0,0 +1,101 @@
+use serde_json::Value;
+
+use super::{resolved_arg, Data, Expression, Operator, PartialResult, Residual, ResidualResult};
+
+/// Takes pairs of a name and a value, followed by the body. Each value is computed once and bound
+/// to its name, `var` operations in the body and in later values resolve the name to it.
+pub fn compute(args: &[Expression], data: &Data) -> Value {
+    let (body, pairs) = match args.split_last() {
+        Some(split) => split,
+        None => return Value::Null,
+    };
+
+    let mut bindings = Vec::with_capacity(pairs.len() / 2);
+    for (i, pair) in pairs.chunks(2).enumerate() {
+        let value = pair
+            .get(1)
+            .map(|arg| arg.compute(&data.bind(&bindings).argument(2 * i + 1)))
+            .unwrap_or(Value::Null);
+        bindings.push((name(&pair[0]), Some(value)));
+    }
+
+    body.compute(&data.bind(&bindings).argument(pairs.len()))
+}
+
+// binds the names of Ambiguous values as unknown, the body is only Ambiguous if it uses them
+pub fn partial_compute(args: &[Expression], data: &Data) -> PartialResult {
+    let (body, pairs) = match args.split_last() {
+        Some(split) => split,
+        None => return Ok(Value::Null),
+    };
+
+    let mut bindings = Vec::with_capacity(pairs.len() / 2);
+    for (i, pair) in pairs.chunks(2).enumerate() {
+        let value = match pair.get(1) {
+            Some(arg) => arg
+                .partial_compute(&data.bind(&bindings).argument(2 * i + 1))
+                .ok(),
+            None => Some(Value::Null),
+        };
+        bindings.push((name(&pair[0]), value));
+    }
+
+    body.partial_compute(&data.bind(&bindings).argument(pairs.len()))
+}
+
+// keeps all bindings in the residual, since the residual of the body and of later values may
+// still use them. Resolved names are replaced by their value though.
+pub fn residual_compute(args: &[Expression], data: &Data) -> ResidualResult {
+    let (body, pairs) = match args.split_last() {
+        Some(split) => split,
+        None => return Ok(Value::Null),
+    };
+
+    let mut residual_args = Vec::with_capacity(args.len());
+    let mut bindings = Vec::with_capacity(pairs.len() / 2);
+    for (i, pair) in pairs.chunks(2).enumerate() {
+        let (value, residual) = match pair.get(1) {
+            Some(arg) => match arg.residual_compute(&data.bind(&bindings).argument(2 * i + 1)) {
+                Ok(value) => (Some(value.clone()), resolved_arg(value, arg)),
+                Err(Residual(expr)) => (None, expr),
+            },
+            None => (Some(Value::Null), Expression::Constant(Value::Null)),
+        };
+        residual_args.push(pair[0].clone());
+        residual_args.push(residual);
+        bindings.push((name(&pair[0]), value));
+    }
+
+    match body.residual_compute(&data.bind(&bindings).argument(pairs.len())) {
+        Ok(value) => Ok(value),
+        Err(Residual(expr)) => {
+            residual_args.push(expr);
+            Err(Residual(Expression::Computed(Operator::Let, residual_args)))
+        }
+    }
+}
+
+/// Returns the name of a binding. Parsing ensures it is a constant string.
+fn name(arg: &Expression) -> &str {
+    match arg {
+        Expression::Constant(Value::String(name)) => name,
+        _ => "",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute_const;
+    use serde_json::json;
+
+    #[test]
+    fn constant_body() {
+        assert_eq!(compute_const!(), json!(null));
+        assert_eq!(compute_const!(json!(1)), json!(1));
+        assert_eq!(
+            compute_const!(json!("x"), json!(2), json!("body")),
+            json!("body")
+        );
+    }
+}
@@ -1,15 +1,16 @@
 use serde_json::Value;
 
 use super::{Data, Expression, PartialResult};
+use crate::context;
 
-/// Logs the first value to console, then passes it through unmodified.
+/// Logs the first value to the log sink of the evaluation, then passes it through unmodified.
 pub fn compute(args: &[Expression], data: &Data) -> Value {
     let a = args
-        .get(0)
-        .map(|arg| arg.compute(data))
+        .first()
+        .map(|arg| arg.compute(&data.argument(0)))
         .unwrap_or(Value::Null);
 
-    println!("{}", a);
+    context::log(data.context(), &a);
 
     a
 }
@@ -17,11 +18,11 @@ pub fn compute(args: &[Expression], data: &Data) -> Value {
 // early returns on finding the arg as Ambiguous
 pub fn partial_compute(args: &[Expression], data: &Data) -> PartialResult {
     let a = args
-        .get(0)
-        .map(|arg| arg.partial_compute(data))
+        .first()
+        .map(|arg| arg.partial_compute(&data.argument(0)))
         .unwrap_or(Ok(Value::Null))?;
 
-    println!("{}", a);
+    context::log(data.context(), &a);
 
     Ok(a)
 }
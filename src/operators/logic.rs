@@ -1,14 +1,17 @@
 use serde_json::{Number, Value};
 
+#[cfg(feature = "decimal")]
+use super::decimal;
+
 /// See http://jsonlogic.com/truthy.html
 pub fn is_truthy(value: &Value) -> bool {
     match value {
         Value::Array(arr) => !arr.is_empty(),
         Value::Bool(b) => *b,
         Value::Null => false,
-        Value::Number(num) => num.as_f64().unwrap() != 0f64,
+        Value::Number(num) => number_to_f64(num) != 0f64,
         Value::Object(_) => true,
-        Value::String(s) => s != "",
+        Value::String(s) => !s.is_empty(),
     }
 }
 
@@ -45,11 +48,11 @@ pub fn is_abstract_equal(a: &Value, b: &Value) -> bool {
         (Null, _) | (_, Null) => false,
         // 4. If Type(a) is number and Type(b) is string, return a == ToNumber(b).
         (Number(a), String(_)) => coerce_to_f64(b)
-            .map(|b| a.as_f64().unwrap() == b)
+            .map(|b| number_to_f64(a) == b)
             .unwrap_or(false),
         // 5. If Type(a) is string and Type(b) is number, return ToNumber(a) == b.
         (String(_), Number(b)) => coerce_to_f64(a)
-            .map(|a| a == b.as_f64().unwrap())
+            .map(|a| a == number_to_f64(b))
             .unwrap_or(false),
         // 6. If Type(a) is bool return ToNumber(a)==b
         (Bool(_), _) => coerce_to_f64(a)
@@ -82,6 +85,16 @@ pub fn less_than(a: &Value, b: &Value) -> bool {
         (Bool(_), Bool(_)) => false,
         (Object(_), _) | (_, Object(_)) => false,
         (String(a), String(b)) => a < b,
+        // Compare integers exactly, they may not be representable as floats.
+        (Number(a), Number(b)) if is_integer(a) && is_integer(b) => {
+            match (a.as_i64(), b.as_i64()) {
+                (Some(a), Some(b)) => a < b,
+                // At least one of them is a u64 above the range of i64.
+                (Some(_), None) => true,
+                (None, Some(_)) => false,
+                (None, None) => a.as_u64() < b.as_u64(),
+            }
+        }
         // Combinations where both operands will be coerced to strings:
         //   Arrays will be converted to a primitive (i.e. a string). (1.)
         //   Strings will be compared lexically. (3.)
@@ -91,6 +104,11 @@ pub fn less_than(a: &Value, b: &Value) -> bool {
         // Combinations where both operands will be coerced to numbers:
         //   In every other combination the operands will be converted to numbers in the end. (4.)
         (Null, _) | (_, Null) | (Number(_), _) | (_, Number(_)) | (Bool(_), _) | (_, Bool(_)) => {
+            // Compare decimals exactly, floats may round them to the same number.
+            #[cfg(feature = "decimal")]
+            if let (Some(a), Some(b)) = (decimal::from_value(a), decimal::from_value(b)) {
+                return a < b;
+            }
             match (coerce_to_f64(a), coerce_to_f64(b)) {
                 (Some(a), Some(b)) => a < b,
                 _ => false,
@@ -140,11 +158,11 @@ pub fn coerce_to_f64(val: &Value) -> Option<f64> {
         Value::Bool(true) => Some(1f64),
         Value::Bool(false) => Some(0f64),
         Value::Null => Some(0f64),
-        Value::Number(num) => num.as_f64(),
+        Value::Number(num) => Some(number_to_f64(num)),
         Value::Object(_) => None,
         Value::String(s) => {
             let s = s.trim();
-            if s == "" {
+            if s.is_empty() {
                 Some(0f64)
             } else {
                 s.parse::<f64>().ok()
@@ -153,6 +171,15 @@ pub fn coerce_to_f64(val: &Value) -> Option<f64> {
     }
 }
 
+/// Turns the result of a numeric operation into a JSON number. NaN and infinite results cannot be
+/// represented in JSON and become `Value::Null`, like `JSON.stringify` does in javascript.
+pub fn number_value(num: f64) -> Value {
+    match Number::from_f64(num) {
+        Some(num) => Value::Number(num),
+        None => Value::Null,
+    }
+}
+
 /// From https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/parseFloat
 /// parseFloat is a top-level function and not a method of any object.
 ///     - If parseFloat encounters a character other than a plus sign (+), minus sign
@@ -171,7 +198,7 @@ pub fn coerce_to_f64(val: &Value) -> Option<f64> {
 /// This function does not support BigInt syntax, since JSON does not support it.
 pub fn parse_float(val: &Value) -> Option<f64> {
     match val {
-        Value::Number(num) => Some(num.as_f64().unwrap()),
+        Value::Number(num) => Some(number_to_f64(num)),
         Value::String(s) => {
             let s = s.trim();
             let mut end = 0;
@@ -199,6 +226,10 @@ pub fn parse_float(val: &Value) -> Option<f64> {
     }
 }
 
+fn is_integer(num: &Number) -> bool {
+    num.is_i64() || num.is_u64()
+}
+
 #[allow(clippy::float_cmp)]
 fn equal_numbers(a: &Number, b: &Number) -> bool {
     // Avoid float compare if possible.
@@ -207,7 +238,21 @@ fn equal_numbers(a: &Number, b: &Number) -> bool {
     } else if a.is_i64() && b.is_i64() {
         a.as_i64().unwrap() == b.as_i64().unwrap()
     } else {
-        a.as_f64().unwrap() == b.as_f64().unwrap()
+        #[cfg(feature = "decimal")]
+        if let (Some(a), Some(b)) = (decimal::from_number(a), decimal::from_number(b)) {
+            return a == b;
+        }
+        number_to_f64(a) == number_to_f64(b)
+    }
+}
+
+/// Returns the number as a float. Numbers beyond the range of floats, which only exist with the
+/// `decimal` feature, become infinite like javascript parses them.
+fn number_to_f64(num: &Number) -> f64 {
+    match num.as_f64() {
+        Some(num) => num,
+        None if num.to_string().starts_with('-') => f64::NEG_INFINITY,
+        None => f64::INFINITY,
     }
 }
 
@@ -216,12 +261,13 @@ fn equal_numbers(a: &Number, b: &Number) -> bool {
 // but in the end it boils down to arr.toString()
 fn arr_to_primitive_str(arr: &[Value]) -> String {
     arr.iter()
-        .map(|el| coerce_to_str(el))
+        .map(coerce_to_str)
         .collect::<Vec<String>>()
         .join(",")
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
     use serde_json::json;
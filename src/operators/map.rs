@@ -4,19 +4,24 @@ use super::{Data, Expression, PartialResult};
 
 /// You can use `map` to perform an action on every member of an array. Note, that inside the
 /// logic being used to map, var operations are relative to the array element being worked on.
+/// Paths starting with `../` refer to the data outside of the `map`, paths starting with `$root.`
+/// to the data the rule is applied to, and `$index` is the index of the element.
 pub fn compute(args: &[Expression], data: &Data) -> Value {
-    let arr = match args.get(0).map(|arg| arg.compute(data)) {
-        Some(Value::Array(arr)) => arr,
-        _ => Vec::with_capacity(0),
+    let value = args
+        .first()
+        .map(|arg| arg.compute_borrowed(&data.argument(0)));
+    let arr = match value.as_deref() {
+        Some(Value::Array(arr)) => arr.as_slice(),
+        _ => &[],
     };
     let op = match args.get(1) {
         Some(expr) => expr,
-        None => &Expression::Constant(&Value::Null),
+        None => &Expression::Constant(Value::Null),
     };
 
     let mut result = Vec::with_capacity(arr.len());
-    for elem in arr.iter() {
-        let mapped_value = op.compute(&Data::from_json(elem));
+    for (index, elem) in arr.iter().enumerate() {
+        let mapped_value = op.compute(&data.argument(1).scoped_element(elem, index));
         result.push(mapped_value);
     }
 
@@ -26,8 +31,8 @@ pub fn compute(args: &[Expression], data: &Data) -> Value {
 // early returns on finding any Ambiguous arg
 pub fn partial_compute(args: &[Expression], data: &Data) -> PartialResult {
     let arr = match args
-        .get(0)
-        .map(|arg| arg.partial_compute(data))
+        .first()
+        .map(|arg| arg.partial_compute(&data.argument(0)))
         .transpose()?
     {
         Some(Value::Array(arr)) => arr,
@@ -35,12 +40,12 @@ pub fn partial_compute(args: &[Expression], data: &Data) -> PartialResult {
     };
     let op = match args.get(1) {
         Some(expr) => expr,
-        None => &Expression::Constant(&Value::Null),
+        None => &Expression::Constant(Value::Null),
     };
 
     let mut result = Vec::with_capacity(arr.len());
-    for elem in arr.iter() {
-        let mapped_value = op.partial_compute(&Data::from_json(elem))?;
+    for (index, elem) in arr.iter().enumerate() {
+        let mapped_value = op.partial_compute(&data.argument(1).scoped_element(elem, index))?;
         result.push(mapped_value);
     }
 
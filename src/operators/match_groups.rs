@@ -0,0 +1,89 @@
+use regex::{Captures, Match, Regex};
+use serde_json::{Map, Value};
+
+use super::{logic, regex_match, Data, Expression, PartialResult};
+
+/// Expects 2 to 3 arguments, the string, the regex pattern and the regex flag(s) [optional].
+/// Returns the captures of the first match as an array that starts with the whole match, or as an
+/// object of the named groups if the pattern has any. Groups that did not take part in the match
+/// are `null`. Returns `null` if the string does not match.
+pub fn compute(args: &[Expression], data: &Data) -> Value {
+    let values: Vec<Value> = args
+        .iter()
+        .enumerate()
+        .map(|(i, arg)| arg.compute(&data.argument(i)))
+        .collect();
+    match_groups(data, &values)
+}
+
+// early returns on finding any Ambiguous arg
+pub fn partial_compute(args: &[Expression], data: &Data) -> PartialResult {
+    let values = args
+        .iter()
+        .enumerate()
+        .map(|(i, arg)| arg.partial_compute(&data.argument(i)))
+        .collect::<Result<Vec<Value>, _>>()?;
+    Ok(match_groups(data, &values))
+}
+
+fn match_groups(data: &Data, values: &[Value]) -> Value {
+    let (text, pattern) = match values {
+        [text, pattern, ..] => (logic::coerce_to_str(text), pattern),
+        _ => return Value::Null,
+    };
+
+    regex_match::apply_regex(data, pattern, values.get(2), |re| {
+        re.captures(&text).map(|captures| groups(re, &captures))
+    })
+    .flatten()
+    .unwrap_or(Value::Null)
+}
+
+fn groups(re: &Regex, captures: &Captures) -> Value {
+    let group = |m: Option<Match>| match m {
+        Some(m) => Value::String(m.as_str().to_owned()),
+        None => Value::Null,
+    };
+
+    if re.capture_names().any(|name| name.is_some()) {
+        let groups: Map<String, Value> = re
+            .capture_names()
+            .flatten()
+            .map(|name| (name.to_owned(), group(captures.name(name))))
+            .collect();
+        Value::Object(groups)
+    } else {
+        Value::Array(captures.iter().map(group).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute_const;
+    use serde_json::json;
+
+    #[test]
+    fn captures() {
+        assert_eq!(compute_const!(), Value::Null);
+        assert_eq!(compute_const!(json!("abc")), Value::Null);
+        assert_eq!(
+            compute_const!(json!("4111111111111111"), json!("^(\\d{6})(\\d*)$")),
+            json!(["4111111111111111", "411111", "1111111111"])
+        );
+        assert_eq!(
+            compute_const!(json!("ab"), json!("(a)|(b)")),
+            json!(["a", "a", null])
+        );
+        assert_eq!(
+            compute_const!(
+                json!("App/1.2 (build 345)"),
+                json!("BUILD (?P<build>\\d+)"),
+                json!("i")
+            ),
+            json!({ "build": "345" })
+        );
+        assert_eq!(compute_const!(json!("abc"), json!("\\d")), Value::Null);
+        assert_eq!(compute_const!(json!("abc"), json!("(")), Value::Null);
+    }
+}
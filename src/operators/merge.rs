@@ -5,8 +5,8 @@ use super::{Data, Expression, PartialResult};
 pub fn compute(args: &[Expression], data: &Data) -> Value {
     let mut result: Vec<Value> = vec![];
 
-    for arg in args {
-        let arg = arg.compute(data);
+    for (i, arg) in args.iter().enumerate() {
+        let arg = arg.compute(&data.argument(i));
         match arg {
             Value::Array(arr) => result.extend(arr.iter().cloned()),
             _ => result.push(arg),
@@ -20,8 +20,8 @@ pub fn compute(args: &[Expression], data: &Data) -> Value {
 pub fn partial_compute(args: &[Expression], data: &Data) -> PartialResult {
     let mut result: Vec<Value> = vec![];
 
-    for arg in args {
-        let arg = arg.partial_compute(data)?;
+    for (i, arg) in args.iter().enumerate() {
+        let arg = arg.partial_compute(&data.argument(i))?;
         match arg {
             Value::Array(arr) => result.extend(arr.iter().cloned()),
             _ => result.push(arg),
@@ -1,19 +1,21 @@
-use serde_json::{Number, Value};
+use serde_json::Value;
 
-use super::{logic, Data, Expression, PartialResult};
+use super::integer::{self, Numeric};
+use super::{Data, Expression, PartialResult};
 
 /// Returns the smallest of the given numbers. Arguments that are no numbers are coerced into
 /// numbers. If one argument cannot be coerced or there are not arguments, `Value::Null` will be
 /// returned.
 /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Math/min
 pub fn compute(args: &[Expression], data: &Data) -> Value {
-    let mut min: Option<f64> = None;
+    let mode = integer::mode(data);
+    let mut min: Option<Numeric> = None;
 
-    for arg in args {
-        let arg = arg.compute(data);
-        match (logic::coerce_to_f64(&arg), min) {
+    for (i, arg) in args.iter().enumerate() {
+        let arg = arg.compute(&data.argument(i));
+        match (Numeric::coerce(&arg, mode), min) {
             (Some(num), Some(current_min)) => {
-                if num < current_min {
+                if num.less_than(current_min) {
                     min = Some(num);
                 }
             }
@@ -23,20 +25,21 @@ pub fn compute(args: &[Expression], data: &Data) -> Value {
     }
 
     match min {
-        Some(min) => Value::Number(Number::from_f64(min).unwrap()),
+        Some(min) => min.into_value(),
         None => Value::Null,
     }
 }
 
 // early returns on finding any Ambiguous arg
 pub fn partial_compute(args: &[Expression], data: &Data) -> PartialResult {
-    let mut min: Option<f64> = None;
+    let mode = integer::mode(data);
+    let mut min: Option<Numeric> = None;
 
-    for arg in args {
-        let arg = arg.partial_compute(data)?;
-        match (logic::coerce_to_f64(&arg), min) {
+    for (i, arg) in args.iter().enumerate() {
+        let arg = arg.partial_compute(&data.argument(i))?;
+        match (Numeric::coerce(&arg, mode), min) {
             (Some(num), Some(current_min)) => {
-                if num < current_min {
+                if num.less_than(current_min) {
                     min = Some(num);
                 }
             }
@@ -46,7 +49,7 @@ pub fn partial_compute(args: &[Expression], data: &Data) -> PartialResult {
     }
 
     match min {
-        Some(min) => Ok(Value::Number(Number::from_f64(min).unwrap())),
+        Some(min) => Ok(min.into_value()),
         None => Ok(Value::Null),
     }
 }
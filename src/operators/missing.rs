@@ -1,6 +1,6 @@
 use serde_json::Value;
 
-use super::{Data, Expression, PartialResult};
+use super::{Ambiguous, Data, Expression, PartialResult};
 
 /// Takes an array of data keys. Returns an array of any keys missing from the data object.
 ///
@@ -9,7 +9,10 @@ use super::{Data, Expression, PartialResult};
 /// See https://github.com/jwadhams/json-logic-js/blob/a15f528919346f2ec7d82bd4fc91c41481546c01/logic.js#L145
 pub fn compute(args: &[Expression], data: &Data) -> Value {
     let mut result: Vec<Value> = vec![];
-    let mut args = args.iter().map(|arg| arg.compute(data));
+    let mut args = args
+        .iter()
+        .enumerate()
+        .map(|(i, arg)| arg.compute(&data.argument(i)));
 
     // The list of keys to look up is either the first argument if that is an array or the list
     // of all arguments otherwise.
@@ -29,9 +32,7 @@ pub fn compute(args: &[Expression], data: &Data) -> Value {
     };
 
     for key in keys.iter() {
-        // TODO: Even tough we only look for the existence, the value to the key will be cloned.
-        // Something like Data::has_value without cloning would help.
-        if data.get_value(key).is_none() {
+        if !data.has_value(key) {
             result.push(key.clone());
         }
     }
@@ -43,8 +44,9 @@ pub fn compute(args: &[Expression], data: &Data) -> Value {
 pub fn partial_compute(args: &[Expression], data: &Data) -> PartialResult {
     let mut result: Vec<Value> = vec![];
     let mut args = args
-        .into_iter()
-        .map(|arg| arg.partial_compute(data))
+        .iter()
+        .enumerate()
+        .map(|(i, arg)| arg.partial_compute(&data.argument(i)))
         .collect::<Result<Vec<Value>, _>>()?
         .into_iter();
 
@@ -66,9 +68,10 @@ pub fn partial_compute(args: &[Expression], data: &Data) -> PartialResult {
     };
 
     for key in keys.iter() {
-        // TODO: Even tough we only look for the existence, the value to the key will be cloned.
-        // Something like Data::has_value without cloning would help.
-        if data.get_value(key).is_none() {
+        if data.is_unknown(key) {
+            return Err(Ambiguous);
+        }
+        if !data.has_value(key) {
             result.push(key.clone());
         }
     }
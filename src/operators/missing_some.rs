@@ -1,24 +1,21 @@
 use serde_json::Value;
 
-use super::{logic, Data, Expression, PartialResult};
+use super::{logic, Ambiguous, Data, Expression, PartialResult};
 
 /// Takes a minimum number of data keys that are required, and an array of keys to search for
 /// (same format as `var` or `missing`). Returns an empty array if the minimum is met, or an array
 /// of the missing keys otherwise.
 pub fn compute(args: &[Expression], data: &Data) -> Value {
     let mut min_num = args
-        .get(0)
-        .map(|arg| arg.compute(data))
+        .first()
+        .map(|arg| arg.compute(&data.argument(0)))
         .and_then(|arg| logic::coerce_to_f64(&arg))
         .map(|arg| arg.ceil() as u64)
         .unwrap_or(0);
 
-    let keys = match args.get(1).map(|arg| arg.compute(data)) {
-        Some(arg) => match arg {
-            Value::Array(keys) => keys,
-            _ => return Value::Array(vec![]),
-        },
-        None => return Value::Array(vec![]),
+    let keys = match args.get(1).map(|arg| arg.compute(&data.argument(1))) {
+        Some(Value::Array(keys)) => keys,
+        _ => return Value::Array(vec![]),
     };
 
     let mut result: Vec<&Value> = vec![];
@@ -28,7 +25,7 @@ pub fn compute(args: &[Expression], data: &Data) -> Value {
             return Value::Array(vec![]);
         }
 
-        if data.get_value(arg).is_some() {
+        if data.has_value(arg) {
             min_num -= 1;
         } else {
             result.push(arg);
@@ -40,13 +37,18 @@ pub fn compute(args: &[Expression], data: &Data) -> Value {
 
 // early returns on finding any Ambiguous arg
 pub fn partial_compute(args: &[Expression], data: &Data) -> PartialResult {
-    let mut min_num = match args.get(0) {
-        Some(arg) => logic::coerce_to_f64(&arg.partial_compute(data)?).map(|a| a.ceil() as u64),
+    let mut min_num = match args.first() {
+        Some(arg) => {
+            logic::coerce_to_f64(&arg.partial_compute(&data.argument(0))?).map(|a| a.ceil() as u64)
+        }
         None => None,
     }
     .unwrap_or(0);
 
-    let keys = match args.get(1).map(|arg| arg.partial_compute(data)) {
+    let keys = match args
+        .get(1)
+        .map(|arg| arg.partial_compute(&data.argument(1)))
+    {
         Some(arg) => match arg? {
             Value::Array(keys) => keys,
             _ => return Ok(Value::Array(vec![])),
@@ -61,7 +63,10 @@ pub fn partial_compute(args: &[Expression], data: &Data) -> PartialResult {
             return Ok(Value::Array(vec![]));
         }
 
-        if data.get_value(arg).is_some() {
+        if data.is_unknown(arg) {
+            return Err(Ambiguous);
+        }
+        if data.has_value(arg) {
             min_num -= 1;
         } else {
             result.push(arg);
@@ -4,6 +4,8 @@ mod addition;
 mod all;
 mod and;
 mod cat;
+#[cfg(feature = "decimal")]
+mod decimal;
 mod division;
 mod double_negation;
 mod equality;
@@ -11,6 +13,7 @@ mod filter;
 mod greater_equal_than;
 mod greater_than;
 mod if_else;
+mod integer;
 mod is_in;
 mod jp_ver_eq;
 mod jp_ver_gt;
@@ -20,9 +23,11 @@ mod jp_ver_lteq;
 mod jp_version;
 mod less_equal_than;
 mod less_than;
+mod let_binding;
 mod log;
 mod logic;
 mod map;
+mod match_groups;
 mod max;
 mod merge;
 mod min;
@@ -36,7 +41,10 @@ mod not_equal;
 mod or;
 mod reduce;
 mod regex_match;
+mod replace;
+mod signature;
 mod some;
+mod split_regex;
 mod strict_equality;
 mod strict_not_equal;
 mod substr;
@@ -45,9 +53,17 @@ mod variable;
 
 use serde_json::Value;
 
-use super::expression::{Ambiguous, Expression, PartialResult};
+use super::expression::{Ambiguous, Expression, PartialResult, Residual, ResidualResult};
 use super::Data;
 
+pub(crate) use integer::Arithmetic;
+pub(crate) use logic::is_truthy;
+pub(crate) use regex_match::{
+    check as check_regex, precompile as precompile_regexes, regex_arguments,
+};
+pub use signature::{ArgumentKind, Signature};
+pub(crate) use variable::compute_borrowed as compute_variable;
+
 /// Represents a JsonLogic operator.
 #[derive(Debug, PartialEq, Copy, Clone)]
 pub enum Operator {
@@ -141,18 +157,20 @@ pub enum Operator {
     Merge,
     /// You can use `map` to perform an action on every member of an array. Note, that inside the
     /// logic being used to map, var operations are relative to the array element being worked on.
+    /// Paths starting with `../` refer to the data outside of the `map`, paths starting with
+    /// `$root.` to the data the rule is applied to, and `$index` is the index of the element.
     Map,
     /// You can use `filter` to keep only elements of the array that pass a test. Note, that inside
     /// the logic being used to map, var operations are relative to the array element being worked
-    /// on.
+    /// on, with access to the enclosing data and the index like in `map`.
     ///
     /// Also note, the returned array will have contiguous indexes starting at zero (typical for
     /// JavaScript, Python and Ruby) it will not preserve the source indexes (making it unlike
     /// PHP’s array_filter).
     Filter,
     /// You can use `reduce` to combine all the elements in an array into a single value, like adding
-    /// up a list of numbers. Note, that inside the logic being used to reduce, var operations only
-    /// have access to an object like:
+    /// up a list of numbers. Note, that inside the logic being used to reduce, var operations are
+    /// relative to an object like:
     ///
     /// ```ignore
     /// {
@@ -160,29 +178,47 @@ pub enum Operator {
     ///     "accumulator" : // progress so far, or the initial value
     /// }
     /// ```
+    ///
+    /// Like in `map`, paths starting with `../` and `$root.` refer to the enclosing data and the
+    /// data the rule is applied to, and `$index` is the index of the current element.
     Reduce,
     /// Takes an array as the first argument and a condition as the second argument. Returns `true`
     /// if the condition evaluates to a truthy value for each element of the first parameter.
     ///
     /// `var` operations inside the second argument expression are relative to the array element
-    /// being tested.
+    /// being tested, with access to the enclosing data and the index like in `map`.
     All,
     /// Takes an array as the first argument and a condition as the second argument. Returns `true`
     /// if the condition evaluates to a truthy value for at least one element of the first
     /// parameter.
     ///
     /// `var` operations inside the second argument expression are relative to the array element
-    /// being tested.
+    /// being tested, with access to the enclosing data and the index like in `map`.
     Some,
     /// Takes an array as the first argument and a condition as the second argument. Returns `true`
     /// if the condition evaluates to a falsy value for each element of the first parameter.
     ///
     /// `var` operations inside the second argument expression are relative to the array element
-    /// being tested.
+    /// being tested, with access to the enclosing data and the index like in `map`.
     None,
+    /// Takes pairs of a constant name and a value, followed by the body, and returns the body
+    /// evaluated with the names bound to the values. `var` operations resolve a bound name before
+    /// the data, also inside array operations in the body. Later values may use earlier names.
+    Let,
     /// Expects 2 to 3 arguments, string to check, regex pattern and regex flag(s) [optional].
     /// Returns a boolean based on if the string matched with the string.
     RegexMatch,
+    /// Expects 2 to 3 arguments, string to check, regex pattern and regex flag(s) [optional].
+    /// Returns the captures of the first match as an array starting with the whole match, or as an
+    /// object if the pattern has named groups. Returns `null` if the string does not match.
+    MatchGroups,
+    /// Expects 3 to 4 arguments, string, regex pattern, replacement and regex flag(s) [optional].
+    /// Replaces the first match, or all matches with the `g` flag. The replacement may reference
+    /// groups with `$1` or `$<name>`.
+    Replace,
+    /// Expects 2 to 3 arguments, string to split, regex pattern and regex flag(s) [optional].
+    /// Returns the array of the parts between the matches.
+    SplitRegex,
     /// Juspay Version Equal. Takes exactly 2 arguments, otherwise returns `false`.
     JuspayVerEq,
     /// Juspay Version Greater than. Takes exactly 2 arguments, otherwise returns `false`.
@@ -198,6 +234,7 @@ pub enum Operator {
 impl Operator {
     /// Returns the Operator matching the given string representation. Returns None if the given
     /// string matches no known operator.
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Option<Operator> {
         match s {
             "==" => Some(Operator::Equal),
@@ -234,7 +271,11 @@ impl Operator {
             "all" => Some(Operator::All),
             "some" => Some(Operator::Some),
             "none" => Some(Operator::None),
+            "let" => Some(Operator::Let),
             "match" => Some(Operator::RegexMatch),
+            "match_groups" => Some(Operator::MatchGroups),
+            "replace" => Some(Operator::Replace),
+            "split_regex" => Some(Operator::SplitRegex),
             "jp_ver_eq" => Some(Operator::JuspayVerEq),
             "jp_ver_gt" => Some(Operator::JuspayVerGt),
             "jp_ver_ge" => Some(Operator::JuspayVerGtEq),
@@ -244,6 +285,56 @@ impl Operator {
         }
     }
 
+    /// Returns the string representation of this operator, i.e. the inverse of `from_str`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Operator::Equal => "==",
+            Operator::StrictEqual => "===",
+            Operator::NotEqual => "!=",
+            Operator::StrictNotEqual => "!==",
+            Operator::Variable => "var",
+            Operator::Negation => "!",
+            Operator::DoubleNegation => "!!",
+            Operator::If => "if",
+            Operator::Or => "or",
+            Operator::And => "and",
+            Operator::LessThan => "<",
+            Operator::LessEqualThan => "<=",
+            Operator::GreaterThan => ">",
+            Operator::GreaterEqualThan => ">=",
+            Operator::Missing => "missing",
+            Operator::MissingSome => "missing_some",
+            Operator::Min => "min",
+            Operator::Max => "max",
+            Operator::Addition => "+",
+            Operator::Subtraction => "-",
+            Operator::Multiplication => "*",
+            Operator::Division => "/",
+            Operator::Modulo => "%",
+            Operator::In => "in",
+            Operator::Cat => "cat",
+            Operator::Substr => "substr",
+            Operator::Log => "log",
+            Operator::Merge => "merge",
+            Operator::Map => "map",
+            Operator::Filter => "filter",
+            Operator::Reduce => "reduce",
+            Operator::All => "all",
+            Operator::Some => "some",
+            Operator::None => "none",
+            Operator::Let => "let",
+            Operator::RegexMatch => "match",
+            Operator::MatchGroups => "match_groups",
+            Operator::Replace => "replace",
+            Operator::SplitRegex => "split_regex",
+            Operator::JuspayVerEq => "jp_ver_eq",
+            Operator::JuspayVerGt => "jp_ver_gt",
+            Operator::JuspayVerGtEq => "jp_ver_ge",
+            Operator::JuspayVerLt => "jp_ver_lt",
+            Operator::JuspayVerLtEq => "jp_ver_le",
+        }
+    }
+
     pub fn compute(self, args: &[Expression], data: &Data) -> Value {
         let compute_fn = match self {
             Operator::Addition => addition::compute,
@@ -272,15 +363,19 @@ impl Operator {
             Operator::MissingSome => missing_some::compute,
             Operator::Missing => missing::compute,
             Operator::Map => map::compute,
+            Operator::MatchGroups => match_groups::compute,
             Operator::Modulo => modulo::compute,
             Operator::Multiplication => multiplication::compute,
             Operator::Negation => negation::compute,
             Operator::None => none::compute,
+            Operator::Let => let_binding::compute,
             Operator::NotEqual => not_equal::compute,
             Operator::Or => or::compute,
             Operator::Reduce => reduce::compute,
             Operator::RegexMatch => regex_match::compute,
+            Operator::Replace => replace::compute,
             Operator::Some => some::compute,
+            Operator::SplitRegex => split_regex::compute,
             Operator::StrictEqual => strict_equality::compute,
             Operator::StrictNotEqual => strict_not_equal::compute,
             Operator::Substr => substr::compute,
@@ -319,15 +414,19 @@ impl Operator {
             Operator::MissingSome => missing_some::partial_compute,
             Operator::Missing => missing::partial_compute,
             Operator::Map => map::partial_compute,
+            Operator::MatchGroups => match_groups::partial_compute,
             Operator::Modulo => modulo::partial_compute,
             Operator::Multiplication => multiplication::partial_compute,
             Operator::Negation => negation::partial_compute,
             Operator::None => none::partial_compute,
+            Operator::Let => let_binding::partial_compute,
             Operator::NotEqual => not_equal::partial_compute,
             Operator::Or => or::partial_compute,
             Operator::Reduce => reduce::partial_compute,
             Operator::RegexMatch => regex_match::partial_compute,
+            Operator::Replace => replace::partial_compute,
             Operator::Some => some::partial_compute,
+            Operator::SplitRegex => split_regex::partial_compute,
             Operator::StrictEqual => strict_equality::partial_compute,
             Operator::StrictNotEqual => strict_not_equal::partial_compute,
             Operator::Substr => substr::partial_compute,
@@ -337,12 +436,134 @@ impl Operator {
 
         partial_compute_fn(args, data)
     }
+
+    pub fn residual_compute(self, args: &[Expression], data: &Data) -> ResidualResult {
+        match self {
+            Operator::And => and::residual_compute(args, data),
+            Operator::If => if_else::residual_compute(args, data),
+            Operator::Let => let_binding::residual_compute(args, data),
+            Operator::Or => or::residual_compute(args, data),
+            // The last argument of these operators is evaluated against the array elements (or the
+            // reduce context), so only the arguments before are computed against the data.
+            Operator::All | Operator::Filter | Operator::Map | Operator::None | Operator::Some => {
+                self.residual_compute_scoped(args, data, &[0])
+            }
+            Operator::Reduce => self.residual_compute_scoped(args, data, &[0, 2]),
+            _ => self.residual_compute_args(args, data),
+        }
+    }
+
+    /// Computes the residual of every argument and partially computes the operator with the
+    /// resolved values and the remaining residuals. Some operators can decide the result without
+    /// knowing all arguments, e.g. a `<` between two resolved arguments that are out of order.
+    fn residual_compute_args(self, args: &[Expression], data: &Data) -> ResidualResult {
+        residual_compute_operation(
+            args,
+            data,
+            |_| true,
+            |args, data| self.partial_compute(args, data),
+            |args| Expression::Computed(self, args),
+        )
+    }
+
+    /// Like `residual_compute_args`, but only the arguments at the given indices are computed. All
+    /// others are kept as they are.
+    fn residual_compute_scoped(
+        self,
+        args: &[Expression],
+        data: &Data,
+        indices: &[usize],
+    ) -> ResidualResult {
+        residual_compute_operation(
+            args,
+            data,
+            |index| indices.contains(&index),
+            |args, data| self.partial_compute(args, data),
+            |args| Expression::Computed(self, args),
+        )
+    }
+}
+
+/// The name bound to an unknown value while an operation is partially computed with the residuals
+/// of its arguments.
+const RESIDUAL: &str = "\u{0}residual";
+
+/// Computes the residuals of the arguments for which `computed` returns true and partially
+/// computes the operation with them, keeping the other arguments as they are. The residuals are
+/// not computed again: each is replaced by a `var` of a name bound to an unknown value, which is
+/// ambiguous like the residual. If the result is ambiguous, returns the operation built by
+/// `operation` from the arguments.
+pub(crate) fn residual_compute_operation(
+    args: &[Expression],
+    data: &Data,
+    computed: impl Fn(usize) -> bool,
+    partial_compute: impl Fn(&[Expression], &Data) -> PartialResult,
+    operation: impl FnOnce(Vec<Expression>) -> Expression,
+) -> ResidualResult {
+    let mut residual_args = Vec::with_capacity(args.len());
+    let mut probe_args = Vec::with_capacity(args.len());
+    for (index, arg) in args.iter().enumerate() {
+        let (probe_arg, residual_arg) = if computed(index) {
+            match arg.residual_compute(&data.argument(index)) {
+                Ok(value) => {
+                    let arg = resolved_arg(value, arg);
+                    (arg.clone(), arg)
+                }
+                Err(Residual(expr)) => (
+                    Expression::Computed(
+                        Operator::Variable,
+                        vec![Expression::Constant(Value::String(RESIDUAL.to_owned()))],
+                    ),
+                    expr,
+                ),
+            }
+        } else {
+            (arg.clone(), arg.clone())
+        };
+        probe_args.push(probe_arg);
+        residual_args.push(residual_arg);
+    }
+
+    let bindings = [(RESIDUAL, None)];
+    partial_compute(&probe_args, &data.bind(&bindings))
+        .map_err(|Ambiguous| Residual(operation(residual_args)))
+}
+
+/// Computes the residual of the given argument and turns the result back into an expression.
+pub(crate) fn residual_arg(arg: &Expression, data: &Data) -> Expression {
+    match arg.residual_compute(data) {
+        Ok(value) => resolved_arg(value, arg),
+        Err(Residual(expr)) => expr,
+    }
+}
+
+/// Turns a resolved argument back into an expression for a residual. An object with a single key
+/// would be read back as an operation, so the original argument is kept in that case.
+pub(crate) fn resolved_arg(value: Value, original: &Expression) -> Expression {
+    match value {
+        Value::Object(ref obj) if obj.len() == 1 => original.clone(),
+        value => Expression::Constant(value),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn as_str() {
+        for name in &[
+            "==",
+            "var",
+            "missing_some",
+            "let",
+            "jp_ver_ge",
+            "split_regex",
+        ] {
+            assert_eq!(Operator::from_str(name).map(Operator::as_str), Some(*name));
+        }
+    }
+
     #[allow(clippy::cognitive_complexity)]
     #[test]
     fn from_str() {
@@ -382,8 +603,18 @@ mod tests {
         assert_eq!(Operator::from_str("reduce"), Some(Operator::Reduce));
         assert_eq!(Operator::from_str("all"), Some(Operator::All));
         assert_eq!(Operator::from_str("none"), Some(Operator::None));
+        assert_eq!(Operator::from_str("let"), Some(Operator::Let));
         assert_eq!(Operator::from_str("some"), Some(Operator::Some));
         assert_eq!(Operator::from_str("match"), Some(Operator::RegexMatch));
+        assert_eq!(
+            Operator::from_str("match_groups"),
+            Some(Operator::MatchGroups)
+        );
+        assert_eq!(Operator::from_str("replace"), Some(Operator::Replace));
+        assert_eq!(
+            Operator::from_str("split_regex"),
+            Some(Operator::SplitRegex)
+        );
         assert_eq!(Operator::from_str("jp_ver_eq"), Some(Operator::JuspayVerEq));
         assert_eq!(Operator::from_str("jp_ver_gt"), Some(Operator::JuspayVerGt));
         assert_eq!(
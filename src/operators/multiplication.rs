@@ -1,28 +1,35 @@
-use serde_json::{Number, Value};
+use serde_json::Value;
 
-use super::{logic, Data, Expression, PartialResult};
+use super::integer::{self, Numeric};
+use super::{Data, Expression, Operator, PartialResult};
 
 /// *, takes an arbitrary number of arguments and multiplicates them. Returns `Value::Null` if one
-/// argument cannot be coerced into a number or if no arguments are passed.
+/// argument cannot be coerced into a number, if no arguments are passed or if the product is not
+/// finite.
 /// If only one argument is specified, it is returned as is, to match the behaviour of the
 /// javascript implementation of JsonLogic.
 pub fn compute(args: &[Expression], data: &Data) -> Value {
     match args {
         [] => Value::Null,
-        [arg] => arg.compute(data),
+        [arg] => arg.compute(&data.argument(0)),
         _ => {
-            let mut result = 1f64;
+            let mode = integer::mode(data);
+            let mut result = Numeric::new(1, mode);
 
-            for arg in args {
+            for (i, arg) in args.iter().enumerate() {
                 // Use parseFloat like in the javascript implementation.
                 // parseFloat(null) is NaN, whereas coerce_to_f64 would return 0.
-                match logic::parse_float(&arg.compute(data)) {
-                    Some(num) => result *= num,
+                let num = match Numeric::parse_float(&arg.compute(&data.argument(i)), mode) {
+                    Some(num) => num,
                     None => return Value::Null,
-                }
+                };
+                result = match result.checked_mul(num) {
+                    Some(result) => result,
+                    None => return integer::overflow(Operator::Multiplication, data),
+                };
             }
 
-            Value::Number(Number::from_f64(result).unwrap())
+            result.into_value()
         }
     }
 }
@@ -31,20 +38,26 @@ pub fn compute(args: &[Expression], data: &Data) -> Value {
 pub fn partial_compute(args: &[Expression], data: &Data) -> PartialResult {
     match args {
         [] => Ok(Value::Null),
-        [arg] => arg.partial_compute(data),
+        [arg] => arg.partial_compute(&data.argument(0)),
         _ => {
-            let mut result = 1f64;
+            let mode = integer::mode(data);
+            let mut result = Numeric::new(1, mode);
 
-            for arg in args {
+            for (i, arg) in args.iter().enumerate() {
                 // Use parseFloat like in the javascript implementation.
                 // parseFloat(null) is NaN, whereas coerce_to_f64 would return 0.
-                match logic::parse_float(&arg.partial_compute(data)?) {
-                    Some(num) => result *= num,
+                let num = match Numeric::parse_float(&arg.partial_compute(&data.argument(i))?, mode)
+                {
+                    Some(num) => num,
                     None => return Ok(Value::Null),
-                }
+                };
+                result = match result.checked_mul(num) {
+                    Some(result) => result,
+                    None => return Ok(integer::overflow(Operator::Multiplication, data)),
+                };
             }
 
-            Ok(Value::Number(Number::from_f64(result).unwrap()))
+            Ok(result.into_value())
         }
     }
 }
@@ -66,5 +79,7 @@ mod tests {
             compute_const!(json!(4), json!(2), json!(2), json!(2)),
             json!(32.0)
         );
+        assert_eq!(compute_const!(json!(1e300), json!(1e300)), Value::Null);
+        assert_eq!(compute_const!(json!("1e400"), json!(0)), Value::Null);
     }
 }
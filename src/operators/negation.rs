@@ -1,10 +1,14 @@
-use serde_json::{json, Value};
+use serde_json::Value;
+use std::borrow::Cow;
 
 use super::{logic, Data, Expression, PartialResult};
 
 /// Logical negation ("not"). Takes just one argument.
 pub fn compute(args: &[Expression], data: &Data) -> Value {
-    let a = args.get(0).map(|a| a.compute(data)).unwrap_or(json!(null));
+    let a = args
+        .first()
+        .map(|a| a.compute_borrowed(&data.argument(0)))
+        .unwrap_or(Cow::Owned(Value::Null));
 
     Value::Bool(!logic::is_truthy(&a))
 }
@@ -12,8 +16,8 @@ pub fn compute(args: &[Expression], data: &Data) -> Value {
 // early returns on finding the arg as Ambiguous
 pub fn partial_compute(args: &[Expression], data: &Data) -> PartialResult {
     let a = args
-        .get(0)
-        .map(|a| a.partial_compute(data))
+        .first()
+        .map(|a| a.partial_compute(&data.argument(0)))
         .unwrap_or(Ok(Value::Null))?;
 
     Ok(Value::Bool(!logic::is_truthy(&a)))
@@ -23,6 +27,7 @@ pub fn partial_compute(args: &[Expression], data: &Data) -> PartialResult {
 mod tests {
     use super::*;
     use crate::compute_const;
+    use serde_json::json;
 
     #[test]
     fn test() {
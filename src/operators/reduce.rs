@@ -3,8 +3,8 @@ use serde_json::{json, Value};
 use super::{Data, Expression, PartialResult};
 
 /// You can use `reduce` to combine all the elements in an array into a single value, like adding
-/// up a list of numbers. Note, that inside the logic being used to reduce, var operations only
-/// have access to an object like:
+/// up a list of numbers. Note, that inside the logic being used to reduce, var operations are
+/// relative to an object like:
 ///
 /// ```ignore
 /// {
@@ -12,24 +12,31 @@ use super::{Data, Expression, PartialResult};
 ///     "accumulator" : // progress so far, or the initial value
 /// }
 /// ```
+///
+/// Like in `map`, paths starting with `../` and `$root.` refer to the enclosing data and the data
+/// the rule is applied to, and `$index` is the index of the current element.
 pub fn compute(args: &[Expression], data: &Data) -> Value {
     let initial = match args.get(2) {
-        Some(expr) => expr.compute(data),
+        Some(expr) => expr.compute(&data.argument(2)),
         None => Value::Null,
     };
-    let arr = match args.get(0).map(|arg| arg.compute(data)) {
+    let value = args
+        .first()
+        .map(|arg| arg.compute_borrowed(&data.argument(0)));
+    let arr = match value.as_deref() {
         Some(Value::Array(arr)) => arr,
         _ => return initial,
     };
     let reducer = match args.get(1) {
         Some(expr) => expr,
-        None => &Expression::Constant(&Value::Null),
+        None => &Expression::Constant(Value::Null),
     };
 
     let mut accumulator = initial;
-    for current in arr.iter() {
-        let reduced_value = reducer.compute(&Data::from_json(
+    for (index, current) in arr.iter().enumerate() {
+        let reduced_value = reducer.compute(&data.argument(1).scoped_element(
             &json!({ "current": current, "accumulator": accumulator }),
+            index,
         ));
         accumulator = reduced_value;
     }
@@ -40,12 +47,12 @@ pub fn compute(args: &[Expression], data: &Data) -> Value {
 // early returns on finding any Ambiguous arg
 pub fn partial_compute(args: &[Expression], data: &Data) -> PartialResult {
     let initial = match args.get(2) {
-        Some(expr) => expr.partial_compute(data)?,
+        Some(expr) => expr.partial_compute(&data.argument(2))?,
         None => Value::Null,
     };
     let arr = match args
-        .get(0)
-        .map(|arg| arg.partial_compute(data))
+        .first()
+        .map(|arg| arg.partial_compute(&data.argument(0)))
         .transpose()?
     {
         Some(Value::Array(arr)) => arr,
@@ -53,13 +60,14 @@ pub fn partial_compute(args: &[Expression], data: &Data) -> PartialResult {
     };
     let reducer = match args.get(1) {
         Some(expr) => expr,
-        None => &Expression::Constant(&Value::Null),
+        None => &Expression::Constant(Value::Null),
     };
 
     let mut accumulator = initial;
-    for current in arr.iter() {
-        let reduced_value = reducer.partial_compute(&Data::from_json(
+    for (index, current) in arr.iter().enumerate() {
+        let reduced_value = reducer.partial_compute(&data.argument(1).scoped_element(
             &json!({ "current": current, "accumulator": accumulator }),
+            index,
         ))?;
         accumulator = reduced_value;
     }
@@ -1,79 +1,268 @@
 use regex::Regex;
 use serde_json::{json, Value};
+use std::collections::hash_map::{Entry, HashMap};
 
-use super::{logic, Data, Expression, PartialResult};
+use super::{logic, Data, Expression, Operator, PartialResult};
+use crate::error::{argument_path, Error};
 
 pub fn compute(args: &[Expression], data: &Data) -> Value {
-    let a = match args.get(0) {
-        Some(arg) => arg.compute(data),
+    let a = match args.first() {
+        Some(arg) => arg.compute(&data.argument(0)),
         None => return json!(false),
     };
 
     let b = match args.get(1) {
-        Some(arg) => arg.compute(data),
+        Some(arg) => arg.compute(&data.argument(1)),
         None => return json!(false),
     };
 
-    let mut pattern = logic::coerce_to_str(&b);
-    if let Some(Value::String(mut flags)) = args.get(2).map(|arg| arg.compute(data)) {
-        if let Some(g) = flags.find("g") {
-            flags.remove(g);
-        };
-        if flags.len() > 0 {
-            pattern = format!("(?{}){}", flags, pattern);
-        }
-    }
-
-    match Regex::new(&pattern) {
-        Ok(re) => {
-            let text = &logic::coerce_to_str(&a);
-            serde_json::to_value(re.is_match(text)).unwrap_or(json!(false))
-        }
-        Err(_) => json!(false),
-    }
+    let flags = args.get(2).map(|arg| arg.compute(&data.argument(2)));
+    json!(is_match(data, &a, &b, flags.as_ref()))
 }
 
 // early returns on finding either Ambiguous arg
 pub fn partial_compute(args: &[Expression], data: &Data) -> PartialResult {
-    let a = match args.get(0) {
-        Some(arg) => arg.partial_compute(data)?,
+    let a = match args.first() {
+        Some(arg) => arg.partial_compute(&data.argument(0))?,
         None => return Ok(json!(false)),
     };
 
     let b = match args.get(1) {
-        Some(arg) => arg.partial_compute(data)?,
+        Some(arg) => arg.partial_compute(&data.argument(1))?,
         None => return Ok(json!(false)),
     };
 
-    let mut pattern = logic::coerce_to_str(&b);
-    if let Some(Value::String(mut flags)) = args
+    let flags = args
         .get(2)
-        .map(|arg| arg.partial_compute(data))
-        .transpose()?
-    {
-        if let Some(g) = flags.find("g") {
-            flags.remove(g);
-        };
-        if flags.len() > 0 {
-            pattern = format!("(?{}){}", flags, pattern);
+        .map(|arg| arg.partial_compute(&data.argument(2)))
+        .transpose()?;
+    Ok(json!(is_match(data, &a, &b, flags.as_ref())))
+}
+
+/// Matches the text against the pattern. An invalid pattern does not match.
+fn is_match(data: &Data, text: &Value, pattern: &Value, flags: Option<&Value>) -> bool {
+    let text = &logic::coerce_to_str(text);
+    apply_regex(data, pattern, flags, |re| re.is_match(text)).unwrap_or(false)
+}
+
+/// Calls `f` with the regex for the given pattern and flags of the current operation. Returns
+/// `None` if the pattern or flags are invalid, and aborts the evaluation in strict regex mode.
+pub(crate) fn apply_regex<T, F>(
+    data: &Data,
+    pattern: &Value,
+    flags: Option<&Value>,
+    f: F,
+) -> Option<T>
+where
+    F: FnOnce(&Regex) -> T,
+{
+    let result = regex_source(pattern, flags).and_then(|source| with_regex(&source, data, f));
+    match result {
+        Ok(value) => Some(value),
+        Err(message) => {
+            if let Some(context) = data.context() {
+                if context.settings.strict_regex {
+                    context.fail(Error::InvalidRegex {
+                        pattern: logic::coerce_to_str(pattern),
+                        message,
+                        path: context.path(),
+                    });
+                }
+            }
+            None
         }
     }
+}
+
+/// Returns whether the given flags contain the global flag `g`.
+pub(crate) fn is_global(flags: Option<&Value>) -> bool {
+    matches!(flags, Some(Value::String(flags)) if flags.contains('g'))
+}
 
-    match Regex::new(&pattern) {
-        Ok(re) => {
-            let text = &logic::coerce_to_str(&a);
-            Ok(serde_json::to_value(re.is_match(text)).unwrap_or(json!(false)))
+/// Returns the regex for the given pattern and flags. The global flag `g` is ignored, the flags
+/// `i`, `m` and `s` are passed to the regex as inline flags. Errors with a message for all other
+/// flags, which the regex engine does not support.
+pub(crate) fn regex_source(pattern: &Value, flags: Option<&Value>) -> Result<String, String> {
+    let pattern = logic::coerce_to_str(pattern);
+    let flags = match flags {
+        Some(Value::String(flags)) => flags,
+        _ => return Ok(pattern),
+    };
+
+    let mut inline = String::new();
+    for flag in flags.chars() {
+        match flag {
+            'g' => (),
+            'i' | 'm' | 's' => {
+                if !inline.contains(flag) {
+                    inline.push(flag);
+                }
+            }
+            _ => return Err(format!("Unsupported regex flag '{}'", flag)),
         }
-        Err(_) => Ok(json!(false)),
     }
+
+    if inline.is_empty() {
+        Ok(pattern)
+    } else {
+        Ok(format!("(?{}){}", inline, pattern))
+    }
+}
+
+/// Compiles the given source, returning the message of the regex error if it is invalid.
+pub(crate) fn compile(source: &str) -> Result<Regex, String> {
+    Regex::new(source).map_err(|err| err.to_string())
+}
+
+/// Calls `f` with the compiled regex for the given source. Regexes are taken from the literal
+/// patterns that were compiled with the rule, or from the regex cache of the evaluator. Errors with
+/// a message if the source is not a valid regex.
+pub(crate) fn with_regex<T, F>(source: &str, data: &Data, f: F) -> Result<T, String>
+where
+    F: FnOnce(&Regex) -> T,
+{
+    match data.context() {
+        Some(context) => match context.regexes.get(source) {
+            Some(re) => Ok(f(re)),
+            None => match context.settings.regex_cache.get(source) {
+                Some(re) => Ok(f(&re)),
+                None => compile(source).map(|re| f(&re)),
+            },
+        },
+        None => compile(source).map(|re| f(&re)),
+    }
+}
+
+/// Compiles the patterns of all regex operations in the expression whose pattern and flags are
+/// constants, keyed by their source. `path` is the JSONPath of the expression. Errors if one of
+/// the patterns or flags is invalid.
+pub(crate) fn precompile(
+    expr: &Expression,
+    path: &str,
+    regexes: &mut HashMap<String, Regex>,
+) -> Result<(), Error> {
+    let (name, args) = match expr {
+        Expression::Constant(_) => return Ok(()),
+        Expression::Computed(operator, args) => (operator.as_str(), args),
+        Expression::Custom(operator, args) => (operator.name(), args),
+    };
+
+    if let Expression::Computed(operator, _) = expr {
+        if let Some((pattern, flags)) = literal_arguments(*operator, args) {
+            let invalid = |message| Error::InvalidRegex {
+                pattern: logic::coerce_to_str(pattern),
+                message,
+                path: path.to_owned(),
+            };
+            let source = regex_source(pattern, flags).map_err(invalid)?;
+            if let Entry::Vacant(entry) = regexes.entry(source) {
+                let re = compile(entry.key()).map_err(invalid)?;
+                entry.insert(re);
+            }
+        }
+    }
+
+    for (i, arg) in args.iter().enumerate() {
+        precompile(arg, &argument_path(path, name, i), regexes)?;
+    }
+    Ok(())
+}
+
+/// Checks the pattern and flags of a regex operation, returning the message of the error if they
+/// are invalid.
+pub(crate) fn check(pattern: &Value, flags: Option<&Value>) -> Result<(), String> {
+    regex_source(pattern, flags)
+        .and_then(|source| compile(&source))
+        .map(|_| ())
+}
+
+/// Returns the indices of the pattern and flags arguments if the operator takes a regex.
+pub(crate) fn regex_arguments(operator: Operator) -> Option<(usize, usize)> {
+    match operator {
+        Operator::RegexMatch | Operator::MatchGroups | Operator::SplitRegex => Some((1, 2)),
+        Operator::Replace => Some((1, 3)),
+        _ => None,
+    }
+}
+
+/// Returns the pattern and flags of a regex operation if they are constants.
+fn literal_arguments(operator: Operator, args: &[Expression]) -> Option<(&Value, Option<&Value>)> {
+    let (pattern_index, flags_index) = regex_arguments(operator)?;
+    let pattern = match args.get(pattern_index) {
+        Some(Expression::Constant(pattern)) => pattern,
+        _ => return None,
+    };
+    let flags = match args.get(flags_index) {
+        Some(Expression::Constant(flags)) => Some(flags),
+        Some(_) => return None,
+        None => None,
+    };
+    Some((pattern, flags))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::compute_const;
+    use crate::error::ROOT_PATH;
     use serde_json::json;
 
+    #[test]
+    fn precompile_literal_patterns() {
+        let mut regexes = HashMap::new();
+        let expr = Expression::from_json(&json!({
+            "and": [
+                { "match": [{ "var": "a" }, "^a+$", "gi"] },
+                { "match": [{ "var": "b" }, { "var": "pattern" }] },
+                { "match": [{ "var": "c" }, "b", { "var": "flags" }] },
+                { "match": [{ "var": "d" }, { "var": "pattern" }, "y"] }
+            ]
+        }))
+        .unwrap();
+
+        precompile(&expr, ROOT_PATH, &mut regexes).unwrap();
+        assert_eq!(regexes.len(), 1);
+        assert!(regexes["(?i)^a+$"].is_match("AAA"));
+    }
+
+    #[test]
+    fn precompile_invalid_patterns() {
+        let expr = Expression::from_json(&json!({
+            "or": [{ "var": "a" }, { "match": [{ "var": "b" }, "a(b"] }]
+        }))
+        .unwrap();
+        let err = precompile(&expr, ROOT_PATH, &mut HashMap::new()).unwrap_err();
+        assert_eq!(err.path(), Some("$.or[1]"));
+
+        let expr = Expression::from_json(&json!({ "match": [{ "var": "a" }, "a", "gy"] })).unwrap();
+        let err = precompile(&expr, ROOT_PATH, &mut HashMap::new()).unwrap_err();
+        assert_eq!(
+            err,
+            Error::InvalidRegex {
+                pattern: String::from("a"),
+                message: String::from("Unsupported regex flag 'y'"),
+                path: String::from(ROOT_PATH),
+            }
+        );
+    }
+
+    #[test]
+    fn flags() {
+        assert_eq!(
+            regex_source(&json!("a"), Some(&json!("gim"))),
+            Ok(String::from("(?im)a"))
+        );
+        assert_eq!(
+            regex_source(&json!("a"), Some(&json!("ii"))),
+            Ok(String::from("(?i)a"))
+        );
+        assert_eq!(
+            regex_source(&json!("a"), Some(&json!("g"))),
+            Ok(String::from("a"))
+        );
+        assert!(regex_source(&json!("a"), Some(&json!("u"))).is_err());
+    }
+
     #[test]
     fn regex_result() {
         assert_eq!(compute_const!(), json!(false));
@@ -100,5 +289,6 @@ mod tests {
             compute_const!(json!("testabctest"), json!("^test")),
             json!(true)
         );
+        assert_eq!(compute_const!(json!("a("), json!("a(")), json!(false));
     }
 }
@@ -0,0 +1,156 @@
+use regex::Regex;
+use serde_json::Value;
+
+use super::{logic, regex_match, Data, Expression, PartialResult};
+
+/// Expects 3 to 4 arguments, the string, the regex pattern, the replacement and the regex flag(s)
+/// [optional]. Replaces the first match, or all matches with the global flag `g`.
+///
+/// As in javascript, `$1` to `$99` and `$<name>` in the replacement insert the respective group,
+/// `$&` inserts the whole match and `$$` a literal `$`.
+pub fn compute(args: &[Expression], data: &Data) -> Value {
+    let values: Vec<Value> = args
+        .iter()
+        .enumerate()
+        .map(|(i, arg)| arg.compute(&data.argument(i)))
+        .collect();
+    replace(data, &values)
+}
+
+// early returns on finding any Ambiguous arg
+pub fn partial_compute(args: &[Expression], data: &Data) -> PartialResult {
+    let values = args
+        .iter()
+        .enumerate()
+        .map(|(i, arg)| arg.partial_compute(&data.argument(i)))
+        .collect::<Result<Vec<Value>, _>>()?;
+    Ok(replace(data, &values))
+}
+
+fn replace(data: &Data, values: &[Value]) -> Value {
+    let text = match values.first() {
+        Some(text) => logic::coerce_to_str(text),
+        None => return Value::Null,
+    };
+    let pattern = match values.get(1) {
+        Some(pattern) => pattern,
+        None => return Value::String(text),
+    };
+    let replacement = values.get(2).map(logic::coerce_to_str).unwrap_or_default();
+    let flags = values.get(3);
+
+    let replaced = regex_match::apply_regex(data, pattern, flags, |re| {
+        let template = expand_template(&replacement, re);
+        if regex_match::is_global(flags) {
+            re.replace_all(&text, template.as_str()).into_owned()
+        } else {
+            re.replace(&text, template.as_str()).into_owned()
+        }
+    });
+    Value::String(replaced.unwrap_or(text))
+}
+
+/// Converts a javascript replacement for the given regex to the syntax of the regex crate.
+/// References to groups that do not exist are kept literally, like javascript does.
+fn expand_template(replacement: &str, re: &Regex) -> String {
+    let group_count = re.captures_len();
+    let mut template = String::with_capacity(replacement.len());
+    let mut rest = replacement;
+
+    while let Some(index) = rest.find('$') {
+        template.push_str(&rest[..index]);
+        rest = &rest[index + 1..];
+
+        let digits: String = rest
+            .chars()
+            .take(2)
+            .take_while(char::is_ascii_digit)
+            .collect();
+        let group = (1..=digits.len())
+            .rev()
+            .map(|len| (len, digits[..len].parse::<usize>().unwrap()))
+            .find(|&(_, group)| group > 0 && group < group_count);
+
+        if let Some((len, group)) = group {
+            template.push_str(&format!("${{{}}}", group));
+            rest = &rest[len..];
+        } else if rest.starts_with('&') {
+            template.push_str("${0}");
+            rest = &rest[1..];
+        } else if rest.starts_with('$') {
+            template.push_str("$$");
+            rest = &rest[1..];
+        } else if let Some(name) = rest
+            .strip_prefix('<')
+            .and_then(|named| named.find('>').map(|end| &named[..end]))
+            .filter(|&name| re.capture_names().any(|group| group == Some(name)))
+        {
+            template.push_str(&format!("${{{}}}", name));
+            rest = &rest[name.len() + 2..];
+        } else {
+            template.push_str("$$");
+        }
+    }
+
+    template.push_str(rest);
+    template
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute_const;
+    use serde_json::json;
+
+    fn expand(replacement: &str, pattern: &str) -> String {
+        expand_template(replacement, &Regex::new(pattern).unwrap())
+    }
+
+    #[test]
+    fn template() {
+        let twelve_groups = "(a)".repeat(12);
+        assert_eq!(expand("$1a", "(a)"), "${1}a");
+        assert_eq!(expand("$12", "(a)"), "${1}2");
+        assert_eq!(expand("$12", &twelve_groups), "${12}");
+        assert_eq!(expand("$2", "(a)"), "$$2");
+        assert_eq!(expand("$0 $& $$ $", "a"), "$$0 ${0} $$ $$");
+        assert_eq!(expand("<$<name>>", "(?P<name>a)"), "<${name}>");
+        assert_eq!(expand("<$<other>>", "(?P<name>a)"), "<$$<other>>");
+        assert_eq!(expand("$<name>", "(a)"), "$$<name>");
+    }
+
+    #[test]
+    fn replace() {
+        assert_eq!(compute_const!(), Value::Null);
+        assert_eq!(compute_const!(json!("abc")), json!("abc"));
+        assert_eq!(
+            compute_const!(json!("a-b-c"), json!("-"), json!("+")),
+            json!("a+b-c")
+        );
+        assert_eq!(
+            compute_const!(json!("a-b-c"), json!("-"), json!("+"), json!("g")),
+            json!("a+b+c")
+        );
+        assert_eq!(
+            compute_const!(
+                json!("John Smith"),
+                json!("(\\w+)\\s(\\w+)"),
+                json!("$2, $1")
+            ),
+            json!("Smith, John")
+        );
+        assert_eq!(
+            compute_const!(json!("ABC"), json!("b"), json!("[$&]"), json!("i")),
+            json!("A[B]C")
+        );
+        assert_eq!(compute_const!(json!("abc"), json!("b")), json!("ac"));
+        assert_eq!(
+            compute_const!(json!("abc"), json!("("), json!("x")),
+            json!("abc")
+        );
+        assert_eq!(
+            compute_const!(json!("abc"), json!("(?<x>b)"), json!("[$<x>|$<y>]")),
+            json!("a[b|$<y>]c")
+        );
+    }
+}
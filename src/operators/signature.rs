@@ -0,0 +1,134 @@
+use serde_json::Value;
+
+use super::Operator;
+use crate::registry::Arity;
+
+/// The arguments an operator expects. Operators accept other arguments as well, but mostly return
+/// values that are not meaningful for them, so passing them is likely an authoring mistake.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Signature {
+    pub arity: Arity,
+    /// What the arguments at the respective index must be if they are given. Arguments without an
+    /// entry accept anything.
+    pub arguments: &'static [ArgumentKind],
+}
+
+/// What an operator expects at one of its argument positions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArgumentKind {
+    Any,
+    /// A number, or an operation that may compute one.
+    Number,
+    /// A string, or an operation that may compute one.
+    String,
+    /// An array, or an operation that may compute one.
+    Array,
+    /// An operation, e.g. the logic that is applied to each element by `reduce`.
+    Expression,
+}
+
+impl ArgumentKind {
+    /// Returns whether the given JSON is accepted as the argument. Operations are accepted for all
+    /// kinds, since their result is not known before the rule is applied.
+    pub fn accepts(self, json: &Value) -> bool {
+        let is_operation = matches!(json, Value::Object(object) if object.len() == 1);
+        match self {
+            ArgumentKind::Any => true,
+            ArgumentKind::Number => is_operation || json.is_number(),
+            ArgumentKind::String => is_operation || json.is_string(),
+            ArgumentKind::Array => is_operation || json.is_array(),
+            ArgumentKind::Expression => is_operation,
+        }
+    }
+
+    /// The description of the kind, to be used in messages.
+    pub fn description(self) -> &'static str {
+        match self {
+            ArgumentKind::Any => "any value",
+            ArgumentKind::Number => "a number",
+            ArgumentKind::String => "a string",
+            ArgumentKind::Array => "an array",
+            ArgumentKind::Expression => "an operation",
+        }
+    }
+}
+
+impl Operator {
+    /// Returns the arguments this operator expects.
+    pub fn signature(self) -> Signature {
+        use ArgumentKind::*;
+
+        let (arity, arguments): (Arity, &'static [ArgumentKind]) = match self {
+            Operator::Equal
+            | Operator::StrictEqual
+            | Operator::NotEqual
+            | Operator::StrictNotEqual
+            | Operator::GreaterThan
+            | Operator::GreaterEqualThan
+            | Operator::Division
+            | Operator::Modulo
+            | Operator::In
+            | Operator::JuspayVerEq
+            | Operator::JuspayVerGt
+            | Operator::JuspayVerGtEq => (Arity::exact(2), &[]),
+            // The third argument turns the comparison into a between check.
+            Operator::LessThan
+            | Operator::LessEqualThan
+            | Operator::JuspayVerLt
+            | Operator::JuspayVerLtEq => (Arity::range(2, 3), &[]),
+            Operator::Variable => (Arity::range(0, 2), &[]),
+            Operator::Negation | Operator::DoubleNegation | Operator::Log => (Arity::exact(1), &[]),
+            Operator::If => (Arity::at_least(2), &[]),
+            // Pairs of a name and a value, followed by the body.
+            Operator::Let => (Arity::at_least(1), &[]),
+            Operator::Or
+            | Operator::And
+            | Operator::Missing
+            | Operator::Min
+            | Operator::Max
+            | Operator::Addition
+            | Operator::Multiplication
+            | Operator::Cat
+            | Operator::Merge => (Arity::at_least(1), &[]),
+            Operator::Subtraction => (Arity::range(1, 2), &[]),
+            Operator::MissingSome => (Arity::exact(2), &[Number, Array]),
+            Operator::Substr => (Arity::range(2, 3), &[Any, Number, Number]),
+            Operator::Map | Operator::Filter | Operator::All | Operator::Some | Operator::None => {
+                (Arity::exact(2), &[Array])
+            }
+            Operator::Reduce => (Arity::range(2, 3), &[Array, Expression]),
+            Operator::RegexMatch | Operator::MatchGroups | Operator::SplitRegex => {
+                (Arity::range(2, 3), &[Any, String, String])
+            }
+            Operator::Replace => (Arity::range(3, 4), &[Any, String, String, String]),
+        };
+
+        Signature { arity, arguments }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn accepts() {
+        assert!(ArgumentKind::Number.accepts(&json!(1)));
+        assert!(ArgumentKind::Number.accepts(&json!({ "var": "a" })));
+        assert!(!ArgumentKind::Number.accepts(&json!("1")));
+        assert!(!ArgumentKind::Array.accepts(&json!({ "a": 1, "b": 2 })));
+        assert!(ArgumentKind::Expression.accepts(&json!({ "+": [1, 2] })));
+        assert!(!ArgumentKind::Expression.accepts(&json!(1)));
+    }
+
+    #[test]
+    fn signature() {
+        assert_eq!(Operator::RegexMatch.signature().arity, Arity::range(2, 3));
+        assert_eq!(Operator::JuspayVerLt.signature().arity, Arity::range(2, 3));
+        assert_eq!(
+            Operator::Reduce.signature().arguments,
+            &[ArgumentKind::Array, ArgumentKind::Expression]
+        );
+    }
+}
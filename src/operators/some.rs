@@ -7,9 +7,12 @@ use super::{logic, Ambiguous, Data, Expression, PartialResult};
 /// parameter.
 ///
 /// `var` operations inside the second argument expression are relative to the array element
-/// being tested.
+/// being tested, with access to the enclosing data and the index like in `map`.
 pub fn compute(args: &[Expression], data: &Data) -> Value {
-    let arr = match args.get(0).map(|arg| arg.compute(data)) {
+    let value = args
+        .first()
+        .map(|arg| arg.compute_borrowed(&data.argument(0)));
+    let arr = match value.as_deref() {
         Some(Value::Array(arr)) => arr,
         _ => return Value::Bool(false),
     };
@@ -18,8 +21,8 @@ pub fn compute(args: &[Expression], data: &Data) -> Value {
         None => return Value::Bool(false),
     };
 
-    for elem in arr.iter() {
-        let result = condition.compute(&Data::from_json(&elem));
+    for (index, elem) in arr.iter().enumerate() {
+        let result = condition.compute(&data.argument(1).scoped_element(elem, index));
         if logic::is_truthy(&result) {
             return Value::Bool(true);
         }
@@ -32,8 +35,8 @@ pub fn compute(args: &[Expression], data: &Data) -> Value {
 // early returns on finding Ambiguous first arg and for Ambiguous results, returns Ambiguous when no there are no true results
 pub fn partial_compute(args: &[Expression], data: &Data) -> PartialResult {
     let arr = match args
-        .get(0)
-        .map(|arg| arg.partial_compute(data))
+        .first()
+        .map(|arg| arg.partial_compute(&data.argument(0)))
         .transpose()?
     {
         Some(Value::Array(arr)) => arr,
@@ -46,8 +49,8 @@ pub fn partial_compute(args: &[Expression], data: &Data) -> PartialResult {
 
     let mut is_ambiguous = false;
 
-    for elem in arr.iter() {
-        match condition.partial_compute(&Data::from_json(&elem)) {
+    for (index, elem) in arr.iter().enumerate() {
+        match condition.partial_compute(&data.argument(1).scoped_element(elem, index)) {
             Err(Ambiguous) => is_ambiguous = true,
             Ok(result) if logic::is_truthy(&result) => return Ok(Value::Bool(true)),
             _ => (),
@@ -0,0 +1,65 @@
+use serde_json::Value;
+
+use super::{logic, regex_match, Data, Expression, PartialResult};
+
+/// Expects 2 to 3 arguments, the string, the regex pattern and the regex flag(s) [optional].
+/// Splits the string at every match of the regex and returns the array of parts.
+pub fn compute(args: &[Expression], data: &Data) -> Value {
+    let values: Vec<Value> = args
+        .iter()
+        .enumerate()
+        .map(|(i, arg)| arg.compute(&data.argument(i)))
+        .collect();
+    split(data, &values)
+}
+
+// early returns on finding any Ambiguous arg
+pub fn partial_compute(args: &[Expression], data: &Data) -> PartialResult {
+    let values = args
+        .iter()
+        .enumerate()
+        .map(|(i, arg)| arg.partial_compute(&data.argument(i)))
+        .collect::<Result<Vec<Value>, _>>()?;
+    Ok(split(data, &values))
+}
+
+fn split(data: &Data, values: &[Value]) -> Value {
+    let text = match values.first() {
+        Some(text) => logic::coerce_to_str(text),
+        None => return Value::Array(vec![]),
+    };
+    let pattern = match values.get(1) {
+        Some(pattern) => pattern,
+        None => return Value::Array(vec![Value::String(text)]),
+    };
+
+    let parts = regex_match::apply_regex(data, pattern, values.get(2), |re| {
+        re.split(&text)
+            .map(|part| Value::String(part.to_owned()))
+            .collect()
+    });
+    Value::Array(parts.unwrap_or_else(|| vec![Value::String(text)]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute_const;
+    use serde_json::json;
+
+    #[test]
+    fn split() {
+        assert_eq!(compute_const!(), json!([]));
+        assert_eq!(compute_const!(json!("a,b")), json!(["a,b"]));
+        assert_eq!(
+            compute_const!(json!("a, b,c"), json!(",\\s*")),
+            json!(["a", "b", "c"])
+        );
+        assert_eq!(
+            compute_const!(json!("1a2A3"), json!("a"), json!("i")),
+            json!(["1", "2", "3"])
+        );
+        assert_eq!(compute_const!(json!(""), json!(",")), json!([""]));
+        assert_eq!(compute_const!(json!("a,b"), json!("(")), json!(["a,b"]));
+    }
+}
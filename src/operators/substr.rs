@@ -13,20 +13,20 @@ use super::{logic, Data, Expression, PartialResult};
 /// The third argument limits the length of the returned substring. Give a negative index to
 /// stop that many characters before the end.
 pub fn compute(args: &[Expression], data: &Data) -> Value {
-    let a = match args.get(0).map(|arg| arg.compute(data)) {
+    let a = match args.first().map(|arg| arg.compute(&data.argument(0))) {
         Some(val) => logic::coerce_to_str(&val),
         // Replicates specifics of the javascript implementation.
         None => String::from("undefined"),
     };
     let b = args
         .get(1)
-        .map(|arg| arg.compute(data))
+        .map(|arg| arg.compute(&data.argument(1)))
         .and_then(|val| logic::coerce_to_f64(&val))
         .map(|f| f as i64)
         .unwrap_or(0);
     let c = args
         .get(2)
-        .map(|arg| arg.compute(data))
+        .map(|arg| arg.compute(&data.argument(2)))
         .and_then(|val| logic::coerce_to_f64(&val))
         .map(|f| f as i64);
 
@@ -58,21 +58,24 @@ pub fn compute(args: &[Expression], data: &Data) -> Value {
 
 // early returns on finding any Ambiguous arg
 pub fn partial_compute(args: &[Expression], data: &Data) -> PartialResult {
-    let a = match args.get(0).map(|arg| arg.partial_compute(data)) {
+    let a = match args
+        .first()
+        .map(|arg| arg.partial_compute(&data.argument(0)))
+    {
         Some(val) => logic::coerce_to_str(&val?),
         // Replicates specifics of the javascript implementation.
         None => String::from("undefined"),
     };
     let b = args
         .get(1)
-        .map(|arg| arg.partial_compute(data))
+        .map(|arg| arg.partial_compute(&data.argument(1)))
         .transpose()?
         .and_then(|val| logic::coerce_to_f64(&val))
         .map(|f| f as i64)
         .unwrap_or(0);
     let c = args
         .get(2)
-        .map(|arg| arg.partial_compute(data))
+        .map(|arg| arg.partial_compute(&data.argument(2)))
         .transpose()?
         .and_then(|val| logic::coerce_to_f64(&val))
         .map(|f| f as i64);
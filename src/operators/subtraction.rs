@@ -1,52 +1,64 @@
-use serde_json::{Number, Value};
+use serde_json::Value;
 
-use super::{logic, Data, Expression, PartialResult};
+use super::integer::{self, Numeric};
+use super::{Data, Expression, Operator, PartialResult};
 
 /// "-", takes two numbers and returns the substraction of the them.
 /// If only one argument is passed, returns the negation of that argument.
 /// Returns `Value::Null` one of the arguments cannot be coerced into a number.
 pub fn compute(args: &[Expression], data: &Data) -> Value {
-    let a = match args.get(0).map(|arg| arg.compute(data)) {
+    let a = match args.first().map(|arg| arg.compute(&data.argument(0))) {
         Some(arg) => arg,
         None => return Value::Null,
     };
 
-    match args.get(1).map(|arg| arg.compute(data)) {
-        None => compute_negation(&logic::coerce_to_f64(&a)),
-        Some(b) => compute_substraction(&logic::coerce_to_f64(&a), &logic::coerce_to_f64(&b)),
-    }
+    let mode = integer::mode(data);
+    let result = match args.get(1).map(|arg| arg.compute(&data.argument(1))) {
+        None => compute_negation(Numeric::coerce(&a, mode)),
+        Some(b) => compute_substraction(Numeric::coerce(&a, mode), Numeric::coerce(&b, mode)),
+    };
+    result_value(result, data)
 }
 
 // early returns on finding any Ambiguous arg
 pub fn partial_compute(args: &[Expression], data: &Data) -> PartialResult {
-    let a = match args.get(0).map(|arg| arg.partial_compute(data)) {
+    let a = match args
+        .first()
+        .map(|arg| arg.partial_compute(&data.argument(0)))
+    {
         Some(arg) => arg?,
         None => return Ok(Value::Null),
     };
 
-    match args.get(1).map(|arg| arg.partial_compute(data)) {
-        None => Ok(compute_negation(&logic::coerce_to_f64(&a))),
-        Some(b) => Ok(compute_substraction(
-            &logic::coerce_to_f64(&a),
-            &logic::coerce_to_f64(&b?),
-        )),
-    }
+    let mode = integer::mode(data);
+    let result = match args
+        .get(1)
+        .map(|arg| arg.partial_compute(&data.argument(1)))
+    {
+        None => compute_negation(Numeric::coerce(&a, mode)),
+        Some(b) => compute_substraction(Numeric::coerce(&a, mode), Numeric::coerce(&b?, mode)),
+    };
+    Ok(result_value(result, data))
 }
 
-fn compute_negation(a: &Option<f64>) -> Value {
-    match a {
-        Some(a) => Value::Number(Number::from_f64(-1f64 * a).unwrap()),
-        None => Value::Null,
-    }
+/// Returns `None` if the operand is not a number, `Some(None)` if the integer result overflows.
+fn compute_negation(a: Option<Numeric>) -> Option<Option<Numeric>> {
+    a.map(Numeric::checked_neg)
 }
 
-fn compute_substraction(a: &Option<f64>, b: &Option<f64>) -> Value {
+/// Returns `None` if an operand is not a number, `Some(None)` if the integer result overflows.
+fn compute_substraction(a: Option<Numeric>, b: Option<Numeric>) -> Option<Option<Numeric>> {
     match (a, b) {
-        (Some(a), Some(b)) => match Number::from_f64(a - b) {
-            Some(num) => Value::Number(num),
-            None => Value::Null,
-        },
-        _ => Value::Null,
+        (Some(a), Some(b)) => Some(a.checked_sub(b)),
+        _ => None,
+    }
+}
+
+fn result_value(result: Option<Option<Numeric>>, data: &Data) -> Value {
+    match result {
+        Some(Some(num)) => num.into_value(),
+        Some(None) => integer::overflow(Operator::Subtraction, data),
+        None => Value::Null,
     }
 }
 
@@ -54,6 +66,7 @@ fn compute_substraction(a: &Option<f64>, b: &Option<f64>) -> Value {
 mod tests {
     use super::*;
     use crate::compute_const;
+    use crate::operators::logic;
     use serde_json::json;
 
     #[test]
@@ -9,15 +9,11 @@
 #[macro_export]
 macro_rules! compute_const {
     ($($args:expr),*) => {{
-        #[allow(unused_mut)]
-        let mut args_vec = vec![];
-        $(
-            args_vec.push($args);
-        )*
+        let args_vec: Vec<Value> = vec![$($args),*];
 
         let expressions: Vec<Expression> = args_vec
-            .iter()
-            .map(|arg| Expression::Constant(&arg))
+            .into_iter()
+            .map(Expression::Constant)
             .collect();
         compute(&expressions, &Data::empty())
     }}
@@ -39,7 +35,7 @@ macro_rules! compute_const_with_data {
         let args: &[Value] = $args;
         let expressions: Vec<Expression> = args
             .iter()
-            .map(|ref arg| Expression::Constant(&arg))
+            .map(|arg| Expression::Constant(arg.clone()))
             .collect();
         compute(&expressions, $data)
     }};
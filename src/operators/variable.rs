@@ -1,47 +1,51 @@
 use serde_json::Value;
+use std::borrow::Cow;
 
 use super::{Ambiguous, Data, Expression, PartialResult};
 
 pub fn compute(args: &[Expression], data: &Data) -> Value {
-    let arg = args
-        .get(0)
-        .map(|arg| arg.compute(data))
-        .unwrap_or(Value::Null);
+    compute_borrowed(args, data).into_owned()
+}
 
-    if arg.is_null() {
-        return data.get_plain().clone();
-    }
+/// Like `compute`, but borrows the value from the data instead of cloning it.
+pub fn compute_borrowed<'e>(args: &'e [Expression], data: &Data<'e>) -> Cow<'e, Value> {
+    let arg = args
+        .first()
+        .map(|arg| arg.compute_borrowed(&data.argument(0)))
+        .unwrap_or(Cow::Owned(Value::Null));
 
-    match &arg {
+    match &*arg {
         // Return the whole data object if there is no argument given or the argument is an empty
         // string.
-        Value::Null => data.get_plain().clone(),
-        Value::String(s) if s == "" => data.get_plain().clone(),
-        _ => data.get_value(&arg).unwrap_or_else(|| {
+        Value::Null => data.get_plain(),
+        Value::String(s) if s.is_empty() => data.get_plain(),
+        path => data.get_value(path).unwrap_or_else(|| {
             args.get(1)
-                .map(|arg| arg.compute(data))
-                .unwrap_or(Value::Null)
+                .map(|arg| arg.compute_borrowed(&data.argument(1)))
+                .unwrap_or(Cow::Owned(Value::Null))
         }),
     }
 }
 
-// returns Ambiguous when value for var is not found
+// returns Ambiguous when value for var is not found or bound to an unknown value
 pub fn partial_compute(args: &[Expression], data: &Data) -> PartialResult {
     let arg = args
-        .get(0)
-        .map(|arg| arg.partial_compute(data))
+        .first()
+        .map(|arg| arg.partial_compute(&data.argument(0)))
         .unwrap_or(Ok(Value::Null))?;
 
     match arg {
-        Value::Null => Ok(data.get_plain().clone()),
-        Value::String(s) if s == "" => Ok(data.get_plain().clone()),
-        _ => data.get_value(&arg).map_or_else(
+        Value::Null => Ok(data.get_plain().into_owned()),
+        Value::String(s) if s.is_empty() => Ok(data.get_plain().into_owned()),
+        // A name bound by `let` to an unknown value must not fall back to the default.
+        _ if data.is_unknown(&arg) => Err(Ambiguous),
+        _ => data.get_value(&arg).map(Cow::into_owned).map_or_else(
             || {
                 args.get(1)
-                    .map(|arg| arg.partial_compute(data))
+                    .map(|arg| arg.partial_compute(&data.argument(1)))
                     .unwrap_or(Err(Ambiguous))
             },
-            |res| Ok(res),
+            Ok,
         ),
     }
 }
@@ -193,4 +197,17 @@ mod tests {
             json!(null)
         );
     }
+
+    #[test]
+    fn borrows_data() {
+        let data_json = json!({ "foo": { "bar": [1, 2, 3] } });
+        let data = Data::from_json(&data_json);
+        let args = [Expression::Constant(json!("foo.bar"))];
+
+        match compute_borrowed(&args, &data) {
+            Cow::Borrowed(value) => assert!(std::ptr::eq(value, &data_json["foo"]["bar"])),
+            Cow::Owned(value) => panic!("expected a borrowed value, got {:?}", value),
+        }
+        assert_eq!(compute(&args, &data), json!([1, 2, 3]));
+    }
 }
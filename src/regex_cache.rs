@@ -0,0 +1,130 @@
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// The number of regexes an evaluator keeps by default.
+pub(crate) const DEFAULT_CAPACITY: usize = 256;
+
+/// A bounded cache of compiled regexes, evicting the least recently used regex when it is full.
+/// It is used for patterns that are computed when a rule is applied, while literal patterns are
+/// compiled with the rule.
+#[derive(Debug)]
+pub(crate) struct RegexCache {
+    capacity: usize,
+    entries: Mutex<Entries>,
+}
+
+#[derive(Debug, Default)]
+struct Entries {
+    regexes: HashMap<String, (Arc<Regex>, u64)>,
+    /// Incremented on every access, the entry with the lowest value is the least recently used.
+    clock: u64,
+}
+
+impl RegexCache {
+    /// Creates a cache that holds up to `capacity` regexes. A capacity of 0 disables caching.
+    pub fn new(capacity: usize) -> RegexCache {
+        RegexCache {
+            capacity,
+            entries: Mutex::new(Entries::default()),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the compiled regex for the given source, compiling it if it is not cached. Returns
+    /// `None` if the source is not a valid regex.
+    pub fn get(&self, source: &str) -> Option<Arc<Regex>> {
+        if self.capacity == 0 {
+            return Regex::new(source).ok().map(Arc::new);
+        }
+
+        {
+            let mut entries = self.entries.lock().unwrap();
+            entries.clock += 1;
+            let clock = entries.clock;
+            if let Some((regex, last_used)) = entries.regexes.get_mut(source) {
+                *last_used = clock;
+                return Some(Arc::clone(regex));
+            }
+        }
+
+        // Compile without holding the lock, so other evaluations are not blocked.
+        let regex = Arc::new(Regex::new(source).ok()?);
+
+        let mut entries = self.entries.lock().unwrap();
+        if entries.regexes.len() >= self.capacity && !entries.regexes.contains_key(source) {
+            let oldest = entries
+                .regexes
+                .iter()
+                .min_by_key(|(_, (_, last_used))| *last_used)
+                .map(|(source, _)| source.clone());
+            if let Some(oldest) = oldest {
+                entries.regexes.remove(&oldest);
+            }
+        }
+        let clock = entries.clock;
+        entries
+            .regexes
+            .insert(source.to_owned(), (Arc::clone(&regex), clock));
+
+        Some(regex)
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.entries.lock().unwrap().regexes.len()
+    }
+
+    #[cfg(test)]
+    fn contains(&self, source: &str) -> bool {
+        self.entries.lock().unwrap().regexes.contains_key(source)
+    }
+}
+
+impl Default for RegexCache {
+    fn default() -> RegexCache {
+        RegexCache::new(DEFAULT_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let cache = RegexCache::new(2);
+        assert!(cache.get("a").is_some());
+        assert!(cache.get("b").is_some());
+        // Use "a" again, so "b" is evicted next.
+        assert!(cache.get("a").is_some());
+        assert!(cache.get("c").is_some());
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.contains("a"));
+        assert!(!cache.contains("b"));
+        assert!(cache.contains("c"));
+    }
+
+    #[test]
+    fn shares_compiled_regexes() {
+        let cache = RegexCache::new(2);
+        let first = cache.get("^a+$").unwrap();
+        let second = cache.get("^a+$").unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+    }
+
+    #[test]
+    fn invalid_and_disabled() {
+        let cache = RegexCache::new(2);
+        assert!(cache.get("(").is_none());
+        assert_eq!(cache.len(), 0);
+
+        let cache = RegexCache::new(0);
+        assert!(cache.get("a").unwrap().is_match("a"));
+        assert_eq!(cache.len(), 0);
+    }
+}
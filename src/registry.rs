@@ -0,0 +1,200 @@
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::data::Data;
+use crate::error::Error;
+use crate::expression::{Expression, PartialResult};
+use crate::operators::Operator;
+
+/// A user defined JsonLogic operator that can be registered in an `OperatorRegistry`.
+///
+/// Like the built-in operators, it receives its arguments as unevaluated expressions. Call
+/// `compute` on the arguments that are needed to evaluate them against the data, with the data
+/// returned by `Data::argument` for their index, so that errors, logs and traces report the paths
+/// of the arguments.
+///
+/// # Example
+///
+/// ```
+/// use jsonlogic::data::Data;
+/// use jsonlogic::expression::Expression;
+/// use jsonlogic::registry::{Arity, CustomOperator};
+/// use serde_json::Value;
+///
+/// struct Upper;
+///
+/// impl CustomOperator for Upper {
+///     fn compute(&self, args: &[Expression], data: &Data) -> Value {
+///         match args[0].compute(&data.argument(0)) {
+///             Value::String(s) => Value::String(s.to_uppercase()),
+///             _ => Value::Null,
+///         }
+///     }
+///
+///     fn arity(&self) -> Option<Arity> {
+///         Some(Arity::exact(1))
+///     }
+/// }
+/// ```
+pub trait CustomOperator: Send + Sync {
+    /// Computes the operator with the given arguments.
+    fn compute(&self, args: &[Expression], data: &Data) -> Value;
+
+    /// Computes the operator with the given arguments against incomplete data.
+    ///
+    /// By default all arguments are partially computed and the result is ambiguous if any of them
+    /// is. Otherwise `compute` is called with the resolved arguments.
+    fn partial_compute(&self, args: &[Expression], data: &Data) -> PartialResult {
+        let args = args
+            .iter()
+            .enumerate()
+            .map(|(i, arg)| {
+                arg.partial_compute(&data.argument(i))
+                    .map(Expression::Constant)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(self.compute(&args, data))
+    }
+
+    /// The number of arguments this operator accepts. If `Some`, rules that call the operator with
+    /// a different number of arguments are rejected when they are parsed.
+    fn arity(&self) -> Option<Arity> {
+        None
+    }
+}
+
+/// The number of arguments an operator accepts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Arity {
+    pub min: usize,
+    /// The maximum number of arguments, `None` if there is no upper bound.
+    pub max: Option<usize>,
+}
+
+impl Arity {
+    /// Accepts exactly `n` arguments.
+    pub fn exact(n: usize) -> Arity {
+        Arity {
+            min: n,
+            max: Some(n),
+        }
+    }
+
+    /// Accepts `min` to `max` arguments, including both bounds.
+    pub fn range(min: usize, max: usize) -> Arity {
+        Arity {
+            min,
+            max: Some(max),
+        }
+    }
+
+    /// Accepts `min` or more arguments.
+    pub fn at_least(min: usize) -> Arity {
+        Arity { min, max: None }
+    }
+
+    /// Returns whether `n` arguments are accepted.
+    pub fn contains(&self, n: usize) -> bool {
+        n >= self.min
+            && match self.max {
+                Some(max) => n <= max,
+                None => true,
+            }
+    }
+}
+
+impl fmt::Display for Arity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.max {
+            Some(max) if max == self.min => write!(f, "{}", max),
+            Some(max) => write!(f, "{} to {}", self.min, max),
+            None => write!(f, "at least {}", self.min),
+        }
+    }
+}
+
+/// A custom operator together with the name it was registered under.
+#[derive(Clone)]
+pub struct RegisteredOperator {
+    name: Arc<str>,
+    operator: Arc<dyn CustomOperator>,
+}
+
+impl RegisteredOperator {
+    /// The name the operator was registered under.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub(crate) fn shared_name(&self) -> Arc<str> {
+        Arc::clone(&self.name)
+    }
+
+    pub fn compute(&self, args: &[Expression], data: &Data) -> Value {
+        self.operator.compute(args, data)
+    }
+
+    pub fn partial_compute(&self, args: &[Expression], data: &Data) -> PartialResult {
+        self.operator.partial_compute(args, data)
+    }
+
+    pub fn arity(&self) -> Option<Arity> {
+        self.operator.arity()
+    }
+}
+
+impl fmt::Debug for RegisteredOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("RegisteredOperator")
+            .field(&self.name)
+            .finish()
+    }
+}
+
+impl PartialEq for RegisteredOperator {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && Arc::ptr_eq(&self.operator, &other.operator)
+    }
+}
+
+/// A set of custom operators that are available when parsing JsonLogic rules, in addition to the
+/// built-in operators.
+#[derive(Debug, Clone, Default)]
+pub struct OperatorRegistry {
+    operators: HashMap<String, RegisteredOperator>,
+}
+
+impl OperatorRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> OperatorRegistry {
+        OperatorRegistry::default()
+    }
+
+    /// Registers the operator under the given name, replacing any custom operator that was
+    /// registered under the same name before. Errors if the name belongs to a built-in operator.
+    pub fn register<O>(&mut self, name: &str, operator: O) -> Result<(), Error>
+    where
+        O: CustomOperator + 'static,
+    {
+        if Operator::from_str(name).is_some() {
+            return Err(Error::ReservedOperator {
+                name: name.to_owned(),
+            });
+        }
+
+        let registered = RegisteredOperator {
+            name: Arc::from(name),
+            operator: Arc::new(operator),
+        };
+        self.operators.insert(name.to_owned(), registered);
+        Ok(())
+    }
+
+    /// Returns the custom operator registered under the given name.
+    pub fn get(&self, name: &str) -> Option<&RegisteredOperator> {
+        self.operators.get(name)
+    }
+}
@@ -0,0 +1,211 @@
+use regex::Regex;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::context::Context;
+use crate::data::Data;
+use crate::data_source::DataSource;
+use crate::error::{Error, ROOT_PATH};
+use crate::evaluator::Settings;
+use crate::expression::{Ambiguous, Expression, Residual};
+use crate::operators;
+use crate::registry::OperatorRegistry;
+use crate::simplify::Simplifier;
+use crate::trace::{Trace, Tracer};
+use crate::variables::{self, VariableReference};
+use crate::PartialApplyOutcome;
+
+/// A JsonLogic rule that is parsed once and can then be evaluated against any number of data
+/// objects.
+///
+/// The rule owns its expression tree, so it does not borrow from the json it was created from and
+/// can be stored in other structs or shared between threads, e.g. behind an `Arc`.
+///
+/// Rules implement `Deserialize` and `Serialize`, so they can be part of a configuration that is
+/// checked when it is loaded:
+///
+/// ```
+/// use jsonlogic::Rule;
+/// use serde::Deserialize;
+/// use serde_json::json;
+///
+/// #[derive(Deserialize)]
+/// struct Route {
+///     condition: Rule,
+///     target: String,
+/// }
+///
+/// let route: Route = serde_json::from_value(json!({
+///     "condition": { "in": [{ "var": "country" }, ["DE", "AT"]] },
+///     "target": "eu"
+/// }))
+/// .unwrap();
+/// assert_eq!(route.condition.evaluate(&json!({ "country": "AT" })), Ok(json!(true)));
+///
+/// let invalid = json!({ "condition": { "unknown": [] }, "target": "eu" });
+/// assert!(serde_json::from_value::<Route>(invalid).is_err());
+/// ```
+///
+/// # Example
+///
+/// ```
+/// use jsonlogic::CompiledRule;
+/// use serde_json::{json, Value};
+///
+/// let rule = CompiledRule::new(&json!({ ">": [{ "var": "age" }, 18] })).unwrap();
+///
+/// assert_eq!(rule.evaluate(&json!({ "age": 21 })), Ok(Value::Bool(true)));
+/// assert_eq!(rule.evaluate(&json!({ "age": 16 })), Ok(Value::Bool(false)));
+/// ```
+#[derive(Debug, Clone)]
+pub struct CompiledRule {
+    expression: Expression,
+    settings: Settings,
+    /// The compiled literal patterns of the `match` operations, keyed by their source.
+    regexes: HashMap<String, Regex>,
+}
+
+impl CompiledRule {
+    /// Parses the given JsonLogic rule. Errors if the rule contains an unknown operator, or a
+    /// `match` operation with an invalid literal pattern or flags.
+    pub fn new(json_logic: &Value) -> Result<CompiledRule, Error> {
+        CompiledRule::with_registry(json_logic, &OperatorRegistry::new())
+    }
+
+    /// Parses the given JsonLogic rule, looking up operators that are not built-in in the given
+    /// registry.
+    pub fn with_registry(
+        json_logic: &Value,
+        registry: &OperatorRegistry,
+    ) -> Result<CompiledRule, Error> {
+        CompiledRule::compile(json_logic, registry, Settings::default())
+    }
+
+    pub(crate) fn compile(
+        json_logic: &Value,
+        registry: &OperatorRegistry,
+        settings: Settings,
+    ) -> Result<CompiledRule, Error> {
+        settings.limits.check_depth(json_logic)?;
+        let expression = Expression::from_json_unchecked(json_logic, registry)?;
+        let mut regexes = HashMap::new();
+        operators::precompile_regexes(&expression, ROOT_PATH, &mut regexes)?;
+        Ok(CompiledRule {
+            expression,
+            settings,
+            regexes,
+        })
+    }
+
+    /// Returns the parsed expression tree of this rule.
+    pub fn expression(&self) -> &Expression {
+        &self.expression
+    }
+
+    /// Returns all references to the data in this rule. See `jsonlogic::variables`.
+    pub fn variables(&self) -> Vec<VariableReference> {
+        variables::variables(&self.expression)
+    }
+
+    /// Evaluates the rule against the given data. If the rule does not use any variables, you may
+    /// pass `&Value::Null`.
+    ///
+    /// Errors only if the evaluation is aborted, e.g. by an invalid regex pattern in strict regex
+    /// mode.
+    pub fn evaluate(&self, data: &dyn DataSource) -> Result<Value, Error> {
+        let context = self.context();
+        let value = self.expression.compute(&Data::with_context(data, &context));
+        context.finish(value)
+    }
+
+    /// Evaluates the rule against the given data like `evaluate`, and records every operation that
+    /// is evaluated along the way. See `jsonlogic::apply_with_trace`.
+    pub fn evaluate_with_trace(&self, data: &dyn DataSource) -> Result<(Value, Trace), Error> {
+        let mut context = self.context();
+        context.tracer = Some(Tracer::new());
+        let value = self.expression.compute(&Data::with_context(data, &context));
+        let trace = context.tracer.take().map(Tracer::finish).unwrap();
+        context.finish((value, trace))
+    }
+
+    /// Evaluates the rule against the given, possibly incomplete data. Returns
+    /// `PartialApplyOutcome::Ambiguous` if the result depends on data that is not present.
+    pub fn partial_evaluate(&self, data: &dyn DataSource) -> Result<PartialApplyOutcome, Error> {
+        let context = self.context();
+        let outcome = match self
+            .expression
+            .partial_compute(&Data::with_context(data, &context))
+        {
+            Ok(value) => PartialApplyOutcome::Resolved(value),
+            Err(Ambiguous) => PartialApplyOutcome::Ambiguous,
+        };
+        context.finish(outcome)
+    }
+
+    /// Evaluates the rule against the given, possibly incomplete data. Returns
+    /// `PartialApplyOutcome::Residual` with the simplified rule if the result depends on data
+    /// that is not present.
+    pub fn partial_evaluate_residual(
+        &self,
+        data: &dyn DataSource,
+    ) -> Result<PartialApplyOutcome, Error> {
+        let context = self.context();
+        let outcome = match self
+            .expression
+            .residual_compute(&Data::with_context(data, &context))
+        {
+            Ok(value) => PartialApplyOutcome::Resolved(value),
+            Err(Residual(expr)) => PartialApplyOutcome::Residual(expr.to_json()),
+        };
+        context.finish(outcome)
+    }
+
+    /// Returns a simpler rule that evaluates to the same value for all data, see
+    /// `Expression::simplify`. Constants are computed with the settings of the evaluator this rule
+    /// was compiled with. Errors if a regular expression that the simplification turned into a
+    /// literal is invalid.
+    pub fn simplify(&self) -> Result<CompiledRule, Error> {
+        let expression = Simplifier::new(&self.settings, &self.regexes).simplify(&self.expression);
+        let mut regexes = self.regexes.clone();
+        operators::precompile_regexes(&expression, ROOT_PATH, &mut regexes)?;
+        Ok(CompiledRule {
+            expression,
+            settings: self.settings.clone(),
+            regexes,
+        })
+    }
+
+    /// Creates the context for an evaluation of this rule.
+    fn context(&self) -> Context<'_> {
+        Context::new(&self.settings, &self.regexes)
+    }
+}
+
+/// A `CompiledRule`, under the name it is usually given in configuration structs.
+pub type Rule = CompiledRule;
+
+/// Serializes the rule as its canonical JsonLogic, see `Expression::to_json`. The settings of the
+/// evaluator it was compiled with are not included.
+impl Serialize for CompiledRule {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.expression.serialize(serializer)
+    }
+}
+
+/// Compiles the rule like `CompiledRule::new`. Rules with custom operators have to be compiled with
+/// `CompiledRule::with_registry` instead.
+impl<'de> Deserialize<'de> for CompiledRule {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let json = Value::deserialize(deserializer)?;
+        CompiledRule::new(&json).map_err(de::Error::custom)
+    }
+}
+
+/// Rules are equal if they have the same expression, regardless of the evaluator they were
+/// compiled with.
+impl PartialEq for CompiledRule {
+    fn eq(&self, other: &Self) -> bool {
+        self.expression == other.expression
+    }
+}
@@ -0,0 +1,370 @@
+use regex::Regex;
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::context::Context;
+use crate::data::Data;
+use crate::evaluator::Settings;
+use crate::expression::Expression;
+use crate::operators::{self, is_truthy, Operator};
+
+/// Rewrites expressions into simpler ones that evaluate to the same value, see
+/// `Expression::simplify`.
+pub(crate) struct Simplifier<'a> {
+    /// The settings constant operations are computed with, since e.g. the arithmetic changes their
+    /// results.
+    settings: &'a Settings,
+    regexes: &'a HashMap<String, Regex>,
+}
+
+impl<'a> Simplifier<'a> {
+    pub fn new(settings: &'a Settings, regexes: &'a HashMap<String, Regex>) -> Simplifier<'a> {
+        Simplifier { settings, regexes }
+    }
+
+    pub fn simplify(&self, expr: &Expression) -> Expression {
+        match expr {
+            Expression::Constant(_) => expr.clone(),
+            Expression::Computed(operator, args) => self.simplify_operation(*operator, args),
+            Expression::Custom(operator, args) => Expression::Custom(
+                operator.clone(),
+                args.iter().map(|arg| self.simplify(arg)).collect(),
+            ),
+        }
+    }
+
+    fn simplify_operation(&self, operator: Operator, original: &[Expression]) -> Expression {
+        let mut args: Vec<Expression> = original.iter().map(|arg| self.simplify(arg)).collect();
+
+        if let Some(indices) = operators::regex_arguments(operator) {
+            if invalid_pattern(&args, indices) {
+                // The rule fails to compile because of the invalid literal pattern, which must not
+                // be folded away.
+                if invalid_pattern(original, indices) {
+                    return Expression::Computed(operator, args);
+                }
+                // A computed pattern that became an invalid literal would fail to compile, while it
+                // is only an error when the rule is applied in strict regex mode.
+                let (pattern, flags) = indices;
+                for &index in &[pattern, flags] {
+                    if let Some(arg) = original.get(index) {
+                        args[index] = arg.clone();
+                    }
+                }
+            }
+        }
+
+        let args = match operator {
+            Operator::And
+            | Operator::Or
+            | Operator::Addition
+            | Operator::Multiplication
+            | Operator::Cat
+            | Operator::Merge => flatten(operator, args),
+            _ => args,
+        };
+
+        if foldable(operator) && args.iter().all(is_constant) {
+            let expr = Expression::Computed(operator, args);
+            return match self.compute(&expr) {
+                Some(value) => operators::resolved_arg(value, &expr),
+                None => expr,
+            };
+        }
+
+        match operator {
+            Operator::And => short_circuit(operator, args, false),
+            Operator::Or => short_circuit(operator, args, true),
+            Operator::If => dead_branches(args),
+            // A single argument is cast, so it is kept.
+            Operator::Addition => without_identities(operator, args, is_integer(0), 1),
+            Operator::Cat => without_identities(operator, args, |value| value == "", 1),
+            Operator::Merge => without_identities(
+                operator,
+                args,
+                |value| matches!(value, Value::Array(arr) if arr.is_empty()),
+                1,
+            ),
+            // A single argument is returned as is, without casting it to a number.
+            Operator::Multiplication => without_identities(operator, args, is_integer(1), 2),
+            _ => Expression::Computed(operator, args),
+        }
+    }
+
+    /// Computes an operation with constant arguments. Returns `None` if the evaluation fails, e.g.
+    /// because of an integer overflow, so the rule still fails when it is applied.
+    fn compute(&self, expr: &Expression) -> Option<Value> {
+        let context = Context::new(self.settings, self.regexes);
+        let value = expr.compute(&Data::with_context(&Value::Null, &context));
+        context.finish(value).ok()
+    }
+}
+
+/// Returns whether an operation with constant arguments always evaluates to the same value, i.e.
+/// it does not access the data and has no side effects.
+fn foldable(operator: Operator) -> bool {
+    !matches!(
+        operator,
+        Operator::Variable | Operator::Missing | Operator::MissingSome | Operator::Log
+    )
+}
+
+fn is_constant(expr: &Expression) -> bool {
+    matches!(expr, Expression::Constant(_))
+}
+
+/// Returns whether the pattern and flags at the given indices are constants that are no valid
+/// regex.
+fn invalid_pattern(args: &[Expression], (pattern, flags): (usize, usize)) -> bool {
+    let flags = match args.get(flags) {
+        Some(Expression::Constant(flags)) => Some(flags),
+        Some(_) => return false,
+        None => None,
+    };
+    match args.get(pattern) {
+        Some(Expression::Constant(pattern)) => operators::check_regex(pattern, flags).is_err(),
+        _ => false,
+    }
+}
+
+/// Replaces arguments that are operations of the same associative operator by their arguments. Sums
+/// and products are only flattened in the first argument, since they are computed from left to
+/// right and float arithmetic is not associative.
+fn flatten(operator: Operator, args: Vec<Expression>) -> Vec<Expression> {
+    let left_only = matches!(operator, Operator::Addition | Operator::Multiplication);
+
+    let mut flat = Vec::with_capacity(args.len());
+    for (i, arg) in args.into_iter().enumerate() {
+        match arg {
+            // Without arguments, the operators evaluate to a value that is no identity element.
+            Expression::Computed(nested, nested_args)
+                if nested == operator && !nested_args.is_empty() && (i == 0 || !left_only) =>
+            {
+                flat.extend(nested_args)
+            }
+            arg => flat.push(arg),
+        }
+    }
+    flat
+}
+
+/// Simplifies an `and` or `or` operation, which stops at the first argument whose truthiness is
+/// `stops_if`. A constant like that ends the arguments, other constants are skipped unless they are
+/// the result. An operation with a single argument evaluates to that argument.
+fn short_circuit(operator: Operator, args: Vec<Expression>, stops_if: bool) -> Expression {
+    let last = args.len().saturating_sub(1);
+
+    let mut kept = Vec::with_capacity(args.len());
+    for (i, arg) in args.into_iter().enumerate() {
+        if let Expression::Constant(value) = &arg {
+            if is_truthy(value) == stops_if {
+                kept.push(arg);
+                break;
+            }
+            if i < last {
+                continue;
+            }
+        }
+        kept.push(arg);
+    }
+
+    if kept.len() == 1 {
+        return kept.remove(0);
+    }
+    Expression::Computed(operator, kept)
+}
+
+/// Drops the condition/then pairs of an `if` operation whose condition is a falsy constant. A
+/// condition that is a truthy constant ends the chain, its then value becomes the else value.
+fn dead_branches(args: Vec<Expression>) -> Expression {
+    // Without a then value, the condition is the result.
+    if args.len() < 2 {
+        return args
+            .into_iter()
+            .next()
+            .unwrap_or(Expression::Constant(Value::Null));
+    }
+
+    let mut kept = Vec::with_capacity(args.len());
+    let mut args = args.into_iter();
+    loop {
+        match (args.next(), args.next()) {
+            (Some(condition), Some(then_arg)) => match &condition {
+                Expression::Constant(value) if is_truthy(value) => {
+                    if kept.is_empty() {
+                        return then_arg;
+                    }
+                    kept.push(then_arg);
+                    break;
+                }
+                Expression::Constant(_) => (),
+                _ => {
+                    kept.push(condition);
+                    kept.push(then_arg);
+                }
+            },
+            (else_arg, _) => {
+                match else_arg {
+                    Some(else_arg) if kept.is_empty() => return else_arg,
+                    Some(else_arg) => kept.push(else_arg),
+                    None if kept.is_empty() => return Expression::Constant(Value::Null),
+                    None => (),
+                }
+                break;
+            }
+        }
+    }
+
+    Expression::Computed(Operator::If, kept)
+}
+
+/// Returns a function that checks whether a value is the given integer. Floats are not matched,
+/// since they turn the result of integer arithmetic into a float.
+fn is_integer(num: i64) -> impl Fn(&Value) -> bool {
+    move |value| matches!(value, Value::Number(n) if n.as_i64() == Some(num))
+}
+
+/// Removes the constant arguments that do not change the result, keeping at least `min` arguments.
+fn without_identities(
+    operator: Operator,
+    args: Vec<Expression>,
+    is_identity: impl Fn(&Value) -> bool,
+    min: usize,
+) -> Expression {
+    let mut remaining = args.len();
+    let args = args
+        .into_iter()
+        .filter(|arg| match arg {
+            Expression::Constant(value) if remaining > min && is_identity(value) => {
+                remaining -= 1;
+                false
+            }
+            _ => true,
+        })
+        .collect();
+    Expression::Computed(operator, args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn simplify(json: Value) -> Value {
+        Expression::from_json(&json).unwrap().simplify().to_json()
+    }
+
+    #[test]
+    fn folds_constants() {
+        assert_eq!(simplify(json!({ "+": [1, 2] })), json!(3.0));
+        assert_eq!(
+            simplify(json!({ "==": [{ "var": "a" }, { "cat": ["x", { "substr": ["yz", 1] }] }] })),
+            json!({ "==": [{ "var": ["a"] }, "xz"] })
+        );
+        assert_eq!(
+            simplify(json!({ "map": [[1, 2], { "*": [{ "var": "" }, { "-": [3, 1] }] }] })),
+            json!({ "map": [[1, 2], { "*": [{ "var": [""] }, 2.0] }] })
+        );
+        assert_eq!(
+            simplify(json!({ "missing": ["a"] })),
+            json!({ "missing": ["a"] })
+        );
+        assert_eq!(simplify(json!({ "log": 1 })), json!({ "log": [1] }));
+    }
+
+    #[test]
+    fn flattens() {
+        assert_eq!(
+            simplify(
+                json!({ "and": [{ "var": "a" }, { "and": [{ "var": "b" }, { "var": "c" }] }] })
+            ),
+            json!({ "and": [{ "var": ["a"] }, { "var": ["b"] }, { "var": ["c"] }] })
+        );
+        assert_eq!(
+            simplify(
+                json!({ "cat": [{ "cat": [{ "var": "a" }, "-"] }, { "cat": ["-", { "var": "b" }] }] })
+            ),
+            json!({ "cat": [{ "var": ["a"] }, "-", "-", { "var": ["b"] }] })
+        );
+        assert_eq!(
+            simplify(json!({ "+": [{ "+": [{ "var": "a" }, 1] }, { "+": [{ "var": "b" }, 2] }] })),
+            json!({ "+": [{ "var": ["a"] }, 1, { "+": [{ "var": ["b"] }, 2] }] })
+        );
+    }
+
+    #[test]
+    fn removes_identities() {
+        assert_eq!(
+            simplify(json!({ "and": [true, { "var": "a" }] })),
+            json!({ "var": ["a"] })
+        );
+        assert_eq!(
+            simplify(json!({ "and": [{ "var": "a" }, 0, { "var": "b" }] })),
+            json!({ "and": [{ "var": ["a"] }, 0] })
+        );
+        assert_eq!(
+            simplify(json!({ "or": [false, { "var": "a" }, true] })),
+            json!({ "or": [{ "var": ["a"] }, true] })
+        );
+        assert_eq!(
+            simplify(json!({ "or": ["yes", { "var": "a" }] })),
+            json!("yes")
+        );
+        assert_eq!(
+            simplify(json!({ "+": [0, { "var": "a" }, 0.0] })),
+            json!({ "+": [{ "var": ["a"] }, 0.0] })
+        );
+        assert_eq!(
+            simplify(json!({ "*": [1, { "var": "a" }] })),
+            json!({ "*": [1, { "var": ["a"] }] })
+        );
+        assert_eq!(
+            simplify(json!({ "*": [1, { "var": "a" }, 1, { "var": "b" }] })),
+            json!({ "*": [{ "var": ["a"] }, { "var": ["b"] }] })
+        );
+        assert_eq!(
+            simplify(json!({ "merge": [[], { "var": "a" }] })),
+            json!({ "merge": [{ "var": ["a"] }] })
+        );
+    }
+
+    #[test]
+    fn prunes_dead_branches() {
+        assert_eq!(
+            simplify(json!({ "if": [true, { "var": "a" }, { "var": "b" }] })),
+            json!({ "var": ["a"] })
+        );
+        assert_eq!(
+            simplify(json!({ "if": [{ "==": [1, 2] }, { "var": "a" }] })),
+            json!(null)
+        );
+        assert_eq!(
+            simplify(json!({ "if": [
+                { "var": "a" }, 1,
+                false, 2,
+                { "var": "b" }, 3,
+                "yes", 4,
+                5
+            ] })),
+            json!({ "if": [{ "var": ["a"] }, 1, { "var": ["b"] }, 3, 4] })
+        );
+    }
+
+    #[test]
+    fn keeps_failures() {
+        let settings = Settings {
+            arithmetic: operators::Arithmetic::Integer,
+            ..Settings::default()
+        };
+        let regexes = HashMap::new();
+        let simplifier = Simplifier::new(&settings, &regexes);
+        let expr = Expression::from_json(&json!({ "+": [u64::MAX, 1] })).unwrap();
+        assert_eq!(simplifier.simplify(&expr), expr);
+
+        // The computed pattern would become an invalid literal.
+        assert_eq!(
+            simplify(json!({ "match": [{ "var": "a" }, { "cat": ["(", "a"] }] })),
+            json!({ "match": [{ "var": ["a"] }, { "cat": ["(", "a"] }] })
+        );
+    }
+}
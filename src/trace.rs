@@ -0,0 +1,116 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::cell::RefCell;
+
+use crate::context::Context;
+use crate::data::Data;
+use crate::expression::Expression;
+use crate::operators::Operator;
+
+/// The operations that were evaluated by `apply_with_trace`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Trace {
+    /// The operation at the root of the rule, `None` if the rule is a constant.
+    pub root: Option<TraceNode>,
+}
+
+/// A single evaluation of an operation.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TraceNode {
+    pub operator: String,
+    /// The JSONPath of the operation in the rule, see `error::argument_path`.
+    pub path: String,
+    /// The values the arguments evaluated to, `None` for arguments that were not evaluated. For
+    /// arguments that are evaluated once per array element, like the logic of `map`, this is the
+    /// value of the last evaluation.
+    pub arguments: Vec<Option<Value>>,
+    pub result: Value,
+    /// The indices of the arguments of `and`, `or` and `if` that were skipped, since the result
+    /// was known before they were reached.
+    pub short_circuited: Vec<usize>,
+    /// The evaluations of the operations in the arguments, in the order they happened.
+    pub children: Vec<TraceNode>,
+}
+
+/// An operation whose evaluation is in progress.
+struct Frame {
+    node: TraceNode,
+    short_circuits: bool,
+}
+
+/// Records the operations of an evaluation.
+#[derive(Default)]
+pub(crate) struct Tracer {
+    frames: RefCell<Vec<Frame>>,
+    root: RefCell<Option<TraceNode>>,
+}
+
+impl Tracer {
+    pub fn new() -> Tracer {
+        Tracer::default()
+    }
+
+    /// Computes the expression and records it if it is an operation. Its value is recorded as an
+    /// argument of the enclosing operation if the data was created for one with `Data::argument`.
+    pub fn trace(&self, expr: &Expression, data: &Data, context: &Context) -> Value {
+        let argument = data.argument_index();
+        let (name, args, short_circuits) = match expr {
+            Expression::Constant(value) => {
+                self.record_argument(argument, value);
+                return value.clone();
+            }
+            Expression::Computed(operator, args) => (
+                operator.as_str(),
+                args,
+                matches!(operator, Operator::And | Operator::Or | Operator::If),
+            ),
+            Expression::Custom(operator, args) => (operator.name(), args, false),
+        };
+
+        self.frames.borrow_mut().push(Frame {
+            node: TraceNode {
+                operator: name.to_owned(),
+                path: context.path(),
+                arguments: vec![None; args.len()],
+                result: Value::Null,
+                short_circuited: vec![],
+                children: vec![],
+            },
+            short_circuits,
+        });
+
+        let result = expr.compute_node(data);
+
+        let frame = self.frames.borrow_mut().pop().unwrap();
+        let mut node = frame.node;
+        if frame.short_circuits {
+            node.short_circuited = (0..node.arguments.len())
+                .filter(|&i| node.arguments[i].is_none())
+                .collect();
+        }
+        node.result = result.clone();
+
+        self.record_argument(argument, &result);
+        match self.frames.borrow_mut().last_mut() {
+            Some(parent) => parent.node.children.push(node),
+            None => *self.root.borrow_mut() = Some(node),
+        }
+
+        result
+    }
+
+    /// Stores the value of the argument at the given index in the innermost operation.
+    fn record_argument(&self, argument: Option<usize>, value: &Value) {
+        if let (Some(parent), Some(index)) = (self.frames.borrow_mut().last_mut(), argument) {
+            if let Some(slot) = parent.node.arguments.get_mut(index) {
+                *slot = Some(value.clone());
+            }
+        }
+    }
+
+    pub fn finish(self) -> Trace {
+        Trace {
+            root: self.root.into_inner(),
+        }
+    }
+}
@@ -1,18 +1,35 @@
 use serde_json::Value;
+use std::collections::HashSet;
 
 use crate::{
+    error::{index_path, key_path, ROOT_PATH},
     expression::{self, Expression},
-    operators::Operator,
+    limits::{Limits, DEFAULT_MAX_DEPTH},
+    operators::{self, Operator},
+    variables,
 };
 
 /// Represents the result of a validation.
 pub type ValidationResult = Result<(), ValidationError>;
 
+/// Validates the data a JSON Logic expression is applied to.
+pub type DataValidator = Box<dyn Fn(&Value) -> ValidationResult + Send + Sync>;
+
+/// How serious a validation finding is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// The JSON Logic expression violates the configuration and is rejected.
+    Error,
+    /// The JSON Logic expression is accepted, but likely does not do what it was meant to.
+    Warning,
+}
+
 /// Represents a validation error.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ValidationError {
     pub message: String,
     pub path: String,
+    pub severity: Severity,
 }
 
 impl ValidationError {
@@ -20,26 +37,54 @@ impl ValidationError {
         ValidationError {
             message: message.to_string(),
             path: path.to_string(),
+            severity: Severity::Error,
         }
     }
+
+    /// Creates a finding that does not reject the JSON Logic expression.
+    pub fn warning(message: &str, path: &str) -> Self {
+        ValidationError {
+            severity: Severity::Warning,
+            ..ValidationError::new(message, path)
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
 }
 
 /// Configuration for validating JSON Logic expressions.
+#[derive(Default)]
 pub struct ValidationConfig {
-    // Allowed operators in the JSON Logic expression.
-    // pub allowed_operators: Option<HashSet<String>>,
+    /// If Some, only the given operators may be used in the JSON Logic expression.
+    pub allowed_operators: Option<HashSet<String>>,
 
-    // Maximum depth of the JSON Logic expression tree.
-    // pub max_depth: Option<usize>,
+    /// If Some, the maximum depth of the JSON Logic expression tree. Each nested operation adds a
+    /// level, so `{"var": "a"}` has a depth of 1 and a constant has a depth of 0.
+    pub max_depth: Option<usize>,
 
-    // Required variables that must be present in the JSON Logic expression.
-    // pub required_variables: Option<HashSet<String>>,
+    /// If Some, each of the given variables must be accessed with `var` somewhere in the JSON
+    /// Logic expression.
+    pub required_variables: Option<HashSet<String>>,
 
-    // Allowed variables that can be accessed in the JSON Logic expression.
-    // pub allowed_variables: Option<HashSet<String>>,
+    /// If Some, only the given variables and their nested fields may be accessed with `var` or
+    /// checked with `missing` and `missing_some`, i.e. allowing `user` also allows `user.name`.
+    /// Inside the expressions applied to array elements by `map`, `filter`, `reduce`, `all`,
+    /// `some` and `none`, only the paths that refer to the data with a `$root.` or enough `../`
+    /// prefixes are checked, without the prefixes. Paths relative to the elements do not access
+    /// the data. Variable names and keys that have to be computed are rejected, also inside array
+    /// operations.
+    pub allowed_variables: Option<HashSet<String>>,
+
+    /// If true, checks the number of arguments of each built-in operator and the type of literal
+    /// arguments against the operator's `Signature`, e.g. `<` with a single argument or `reduce`
+    /// with a constant as the logic to apply are rejected.
+    pub check_signatures: bool,
+
+    /// If Some, validates the data a JSON Logic expression is applied to. See `validate_data`.
+    pub data_schema_validator: Option<DataValidator>,
 
-    // Validates the data value against a specific schema.
-    // pub data_schema_validator: Option<Box<dyn Fn(&Value) -> ValidationResult>>,
     /// If Some, ensures all conditions are wrapped in an 'and' block.
     /// Empty JSON logic objects are allowed if allow_empty is true.
     pub require_and_wrapper: Option<RequireAndWrapper>,
@@ -52,32 +97,66 @@ pub struct RequireAndWrapper {
     pub allow_empty: bool,
 }
 
-impl Default for ValidationConfig {
-    fn default() -> Self {
-        ValidationConfig {
-            // allowed_operators: None,
-            // max_depth: None,
-            // required_variables: None,
-            // allowed_variables: None,
-            // data_schema_validator: None,
-            require_and_wrapper: None,
-        }
+/// Validates a JSON Logic expression against the provided configuration. Returns the first error,
+/// warnings are ignored.
+pub fn validate(json_logic: &Value, config: &ValidationConfig) -> ValidationResult {
+    match validate_all(json_logic, config)
+        .into_iter()
+        .find(ValidationError::is_error)
+    {
+        Some(err) => Err(err),
+        None => Ok(()),
     }
 }
 
-/// Validates a JSON Logic expression against the provided configuration.
-pub fn validate(json_logic: &Value, config: &ValidationConfig) -> ValidationResult {
+/// Validates a JSON Logic expression against the provided configuration and returns all errors
+/// and warnings, in the order of the nodes they belong to. The expression is valid if none of them
+/// is an error.
+///
+/// Besides violations of the configuration, warnings are reported for objects that look like an
+/// operation but are treated as literal values, since they have more than one key.
+pub fn validate_all(json_logic: &Value, config: &ValidationConfig) -> Vec<ValidationError> {
+    let mut checker = RuleChecker {
+        config,
+        errors: vec![],
+        variables: HashSet::new(),
+        bound: vec![],
+    };
+
     // Check if the JSON Logic is properly wrapped in an 'and' block if required
     if let Some(require_and) = &config.require_and_wrapper {
-        validate_and_wrapper(json_logic, require_and)?;
+        if let Err(err) = validate_and_wrapper(json_logic, require_and) {
+            checker.errors.push(err);
+        }
+    }
+
+    // The checks recurse, so deeper rules are only reported.
+    if let Err(err) = Limits::default().check_depth(json_logic) {
+        checker.error(
+            format!("Maximum nesting of {} exceeded", DEFAULT_MAX_DEPTH),
+            err.path().unwrap_or(ROOT_PATH),
+        );
+        return checker.errors;
+    }
+
+    checker.check(json_logic, ROOT_PATH, 0, 0);
+    checker.check_required_variables();
+    checker.errors
+}
+
+/// Validates the data a JSON Logic expression is applied to with the `data_schema_validator` of
+/// the configuration, if any.
+pub fn validate_data(data: &Value, config: &ValidationConfig) -> ValidationResult {
+    match &config.data_schema_validator {
+        Some(validator) => validator(data),
+        None => Ok(()),
     }
-    Ok(())
 }
 
 /// Validates that JSON Logic is properly wrapped in an 'and' block.
 fn validate_and_wrapper(json_logic: &Value, config: &RequireAndWrapper) -> ValidationResult {
     let ast = expression::Expression::from_json(json_logic)
-        .map_err(|err| ValidationError::new(&err.to_string(), "$"))?;
+        .map_err(|err| ValidationError::new(&err.to_string(), err.path().unwrap_or("$")))?;
 
     match ast {
         Expression::Constant(Value::Object(obj)) if obj.is_empty() && config.allow_empty => Ok(()),
@@ -92,3 +171,258 @@ fn validate_and_wrapper(json_logic: &Value, config: &RequireAndWrapper) -> Valid
         )),
     }
 }
+
+/// Walks the JSON Logic expression and collects the violations of the operator, depth and variable
+/// restrictions of the configuration.
+struct RuleChecker<'a> {
+    config: &'a ValidationConfig,
+    errors: Vec<ValidationError>,
+    /// The variables that are accessed in the data, without the prefixes that refer to it.
+    variables: HashSet<String>,
+    /// The names bound by the enclosing `let` operations, which are no variables in the data.
+    bound: Vec<String>,
+}
+
+impl<'a> RuleChecker<'a> {
+    /// Checks the node at the given path. `depth` is the number of operations the node is nested
+    /// in and `scopes` the number of array element scopes, see `variables::strip_scope`.
+    fn check(&mut self, json: &Value, path: &str, depth: usize, scopes: usize) {
+        let (operator, value) = match operation(json) {
+            Some(operation) => operation,
+            None => {
+                self.check_literal(json, path);
+                return;
+            }
+        };
+        let depth = depth + 1;
+
+        if let Some(allowed) = &self.config.allowed_operators {
+            if !allowed.contains(operator) {
+                self.error(format!("Operator '{}' is not allowed", operator), path);
+            }
+        }
+
+        if let Some(max_depth) = self.config.max_depth {
+            if depth > max_depth {
+                self.error(format!("Maximum depth of {} exceeded", max_depth), path);
+                // The nested operations exceed the depth as well, report only the outermost.
+                return;
+            }
+        }
+
+        let args = arguments(value, &key_path(path, operator));
+        if self.config.check_signatures {
+            if let Some(op) = Operator::from_str(operator) {
+                self.check_signature(op, &args, path);
+            }
+        }
+        if let Some(indices) = Operator::from_str(operator).and_then(operators::regex_arguments) {
+            self.check_regex(&args, indices, path);
+        }
+        match operator {
+            "var" => {
+                if let Some(name) =
+                    self.check_variable(args.first().map(|(arg, _)| *arg), path, scopes)
+                {
+                    self.variables.insert(name);
+                }
+            }
+            "missing" | "missing_some" => {
+                for key in missing_keys(operator, &args) {
+                    self.check_variable(Some(key), path, scopes);
+                }
+            }
+            _ => (),
+        }
+
+        // Each name of a `let` is visible in the later values and in the body.
+        let outer_bound = self.bound.len();
+        for (i, (arg, arg_path)) in args.iter().enumerate() {
+            // `Option::is_some_and` would raise the minimum Rust version to 1.70.
+            #[allow(clippy::unnecessary_map_or)]
+            let arg_scopes = if i == 1
+                && Operator::from_str(operator).map_or(false, variables::is_array_operator)
+            {
+                scopes + 1
+            } else {
+                scopes
+            };
+            self.check(arg, arg_path, depth, arg_scopes);
+            if operator == "let" && i % 2 == 1 {
+                if let Value::String(name) = args[i - 1].0 {
+                    self.bound.push(name.clone());
+                }
+            }
+        }
+        self.bound.truncate(outer_bound);
+    }
+
+    /// Checks the arguments of the operation at the given path against the operator's signature.
+    fn check_signature(&mut self, operator: Operator, args: &[(&Value, String)], path: &str) {
+        let signature = operator.signature();
+        if !signature.arity.contains(args.len()) {
+            self.error(
+                format!(
+                    "Operator '{}' expects {} arguments, got {}",
+                    operator.as_str(),
+                    signature.arity,
+                    args.len()
+                ),
+                path,
+            );
+        }
+
+        for (kind, (arg, arg_path)) in signature.arguments.iter().zip(args) {
+            if !kind.accepts(arg) {
+                self.error(
+                    format!(
+                        "Operator '{}' expects {} as this argument",
+                        operator.as_str(),
+                        kind.description()
+                    ),
+                    arg_path,
+                );
+            }
+        }
+    }
+
+    /// Checks the pattern and flags of the regex operation at the given path, if they are not
+    /// computed. `indices` are the indices of the pattern and flags arguments.
+    fn check_regex(&mut self, args: &[(&Value, String)], indices: (usize, usize), path: &str) {
+        let (pattern_index, flags_index) = indices;
+        let pattern = match args.get(pattern_index) {
+            Some((pattern, _)) if operation(pattern).is_none() => *pattern,
+            _ => return,
+        };
+        let flags = match args.get(flags_index) {
+            Some((flags, _)) if operation(flags).is_some() => return,
+            Some((flags, _)) => Some(*flags),
+            None => None,
+        };
+
+        if let Err(message) = operators::check_regex(pattern, flags) {
+            self.error(format!("Invalid regex: {}", message), path);
+        }
+    }
+
+    /// Checks the variable that is accessed by the `var`, `missing` or `missing_some` operation at
+    /// the given path, which is nested in `scopes` array element scopes. Only variables in the
+    /// data are checked, their name is returned.
+    fn check_variable(
+        &mut self,
+        name: Option<&Value>,
+        path: &str,
+        scopes: usize,
+    ) -> Option<String> {
+        let written = match name {
+            None | Some(Value::Null) => String::new(),
+            Some(Value::String(name)) => name.clone(),
+            Some(Value::Number(index)) => index.to_string(),
+            Some(_) => {
+                if self.config.allowed_variables.is_some() {
+                    self.error(
+                        String::from("Variable names must be static if variables are restricted"),
+                        path,
+                    );
+                }
+                return None;
+            }
+        };
+        let first_step = written.split('.').next().unwrap_or("");
+        if self.bound.iter().any(|bound| bound == first_step) {
+            return None;
+        }
+        let name = match variables::strip_scope(&written, scopes) {
+            Some((name, 0)) => name.to_owned(),
+            // Relative to an array element, or beyond the data.
+            _ => return None,
+        };
+
+        if let Some(allowed) = &self.config.allowed_variables {
+            if !is_allowed_variable(&name, allowed) {
+                self.error(format!("Variable '{}' is not allowed", written), path);
+            }
+        }
+        Some(name)
+    }
+
+    /// Warns about objects that contain an operator key, but are not operations since they have
+    /// further keys.
+    fn check_literal(&mut self, json: &Value, path: &str) {
+        if let Value::Object(object) = json {
+            if let Some(key) = object.keys().find(|key| Operator::from_str(key).is_some()) {
+                self.errors.push(ValidationError::warning(
+                    &format!(
+                        "Object with more than one key is a literal value, operator '{}' is not applied",
+                        key
+                    ),
+                    path,
+                ));
+            }
+        }
+    }
+
+    fn check_required_variables(&mut self) {
+        if let Some(required) = &self.config.required_variables {
+            let mut missing: Vec<&String> = required.difference(&self.variables).collect();
+            missing.sort();
+            for name in missing {
+                self.errors.push(ValidationError::new(
+                    &format!("Required variable '{}' is not used", name),
+                    ROOT_PATH,
+                ));
+            }
+        }
+    }
+
+    fn error(&mut self, message: String, path: &str) {
+        self.errors.push(ValidationError::new(&message, path));
+    }
+}
+
+/// Returns the operator and its arguments if the given JSON is an operation.
+fn operation(json: &Value) -> Option<(&str, &Value)> {
+    match json {
+        Value::Object(object) if object.len() == 1 => object
+            .iter()
+            .next()
+            .map(|(key, value)| (key.as_str(), value)),
+        _ => None,
+    }
+}
+
+/// Returns the arguments of an operation together with their paths, given the value of the
+/// operator key and its path.
+fn arguments<'v>(value: &'v Value, path: &str) -> Vec<(&'v Value, String)> {
+    match value {
+        Value::Array(arr) => arr
+            .iter()
+            .enumerate()
+            .map(|(i, arg)| (arg, index_path(path, i)))
+            .collect(),
+        Value::Null => vec![],
+        _ => vec![(value, path.to_owned())],
+    }
+}
+
+/// Returns the keys checked by a `missing` or `missing_some` operation with the given arguments.
+/// Keys that are computed are returned as their operation.
+fn missing_keys<'v>(operator: &str, args: &[(&'v Value, String)]) -> Vec<&'v Value> {
+    let keys = match operator {
+        "missing_some" => args.get(1),
+        _ => args.first(),
+    };
+    match keys {
+        Some((Value::Array(keys), _)) => keys.iter().collect(),
+        Some((keys, _)) if operator == "missing_some" && operation(keys).is_some() => vec![keys],
+        Some(_) if operator == "missing" => args.iter().map(|(key, _)| *key).collect(),
+        _ => vec![],
+    }
+}
+
+fn is_allowed_variable(name: &str, allowed: &HashSet<String>) -> bool {
+    allowed.iter().any(|allowed| {
+        name == allowed
+            || (name.starts_with(allowed.as_str()) && name[allowed.len()..].starts_with('.'))
+    })
+}
@@ -0,0 +1,270 @@
+use serde_json::Value;
+
+use crate::data;
+use crate::error::{argument_path, ROOT_PATH};
+use crate::expression::Expression;
+use crate::operators::Operator;
+
+/// A reference to the data in a JsonLogic rule, made with `var`, `missing` or `missing_some`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VariableReference {
+    /// The referenced data path, e.g. `user.name`.
+    pub path: VariablePath,
+    /// The default of a `var` operation. A constant unless the default is computed, then it is
+    /// the JsonLogic of the computation.
+    pub default: Option<Value>,
+    pub kind: ReferenceKind,
+    /// Whether the reference is part of the logic that `map`, `filter`, `reduce`, `all`, `some`
+    /// or `none` apply to each array element. Its path is then relative to the element instead of
+    /// the data. Paths that refer to the data with a `$root.` prefix, or with enough `../`
+    /// prefixes, are not scoped and reported without the prefix.
+    pub scoped: bool,
+    /// The JSONPath of the `var`, `missing` or `missing_some` operation in the rule, see
+    /// `error::argument_path`.
+    pub location: String,
+}
+
+impl VariableReference {
+    /// Returns the referenced data path if it is known before the rule is applied.
+    pub fn static_path(&self) -> Option<&str> {
+        match &self.path {
+            VariablePath::Static(path) => Some(path),
+            VariablePath::Dynamic(_) => None,
+        }
+    }
+}
+
+/// The data path of a `VariableReference`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum VariablePath {
+    /// A path given as a constant. Numbers are converted to a string, `null` to the empty path that
+    /// references the whole data.
+    Static(String),
+    /// A path that is computed when the rule is applied, given as the JsonLogic of the computation.
+    /// Constant booleans, arrays and objects, which are not valid paths, are reported as is.
+    Dynamic(Value),
+}
+
+/// The operation that references a variable.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReferenceKind {
+    /// Reads the variable with `var`.
+    Var,
+    /// Checks whether the variable is present with `missing`.
+    Missing,
+    /// Checks whether the variable is present with `missing_some`.
+    MissingSome,
+}
+
+/// Returns all references to the data in the given expression, in the order they appear in.
+/// References to names bound by a `let` operation are not included.
+pub fn variables(expression: &Expression) -> Vec<VariableReference> {
+    let mut references = vec![];
+    collect(expression, ROOT_PATH, 0, &[], &mut references);
+    references
+}
+
+/// Collects the references in the expression, which is nested in `depth` array element scopes and
+/// in `let` operations that bound the given names.
+fn collect(
+    expr: &Expression,
+    path: &str,
+    depth: usize,
+    bound: &[&str],
+    refs: &mut Vec<VariableReference>,
+) {
+    let (name, args) = match expr {
+        Expression::Constant(_) => return,
+        Expression::Computed(operator, args) => {
+            collect_operation(*operator, args, path, depth, bound, refs);
+            return;
+        }
+        Expression::Custom(operator, args) => (operator.name(), args),
+    };
+
+    for (i, arg) in args.iter().enumerate() {
+        collect(arg, &argument_path(path, name, i), depth, bound, refs);
+    }
+}
+
+fn collect_operation(
+    operator: Operator,
+    args: &[Expression],
+    path: &str,
+    depth: usize,
+    bound: &[&str],
+    refs: &mut Vec<VariableReference>,
+) {
+    match operator {
+        Operator::Variable => {
+            let var_path = args
+                .first()
+                .map(variable_path)
+                .unwrap_or_else(|| VariablePath::Static(String::new()));
+            if is_bound(&var_path, bound) {
+                return;
+            }
+            let (var_path, scoped) = unscope(var_path, depth);
+            refs.push(VariableReference {
+                path: var_path,
+                default: args.get(1).map(Expression::to_json),
+                kind: ReferenceKind::Var,
+                scoped,
+                location: path.to_owned(),
+            })
+        }
+        Operator::Missing => {
+            for arg in args {
+                push_keys(arg, ReferenceKind::Missing, path, depth, bound, refs);
+            }
+        }
+        Operator::MissingSome => {
+            if let Some(keys) = args.get(1) {
+                push_keys(keys, ReferenceKind::MissingSome, path, depth, bound, refs);
+            }
+        }
+        // Each name is visible in the later values and in the body.
+        Operator::Let => {
+            let mut names = bound.to_vec();
+            for (i, arg) in args.iter().enumerate() {
+                collect(
+                    arg,
+                    &argument_path(path, operator.as_str(), i),
+                    depth,
+                    &names,
+                    refs,
+                );
+                if i % 2 == 1 {
+                    if let Expression::Constant(Value::String(name)) = &args[i - 1] {
+                        names.push(name);
+                    }
+                }
+            }
+            return;
+        }
+        _ => (),
+    }
+
+    for (i, arg) in args.iter().enumerate() {
+        let arg_depth = if i == 1 && is_array_operator(operator) {
+            depth + 1
+        } else {
+            depth
+        };
+        collect(
+            arg,
+            &argument_path(path, operator.as_str(), i),
+            arg_depth,
+            bound,
+            refs,
+        );
+    }
+}
+
+/// Pushes a reference for each key that is checked by a `missing` or `missing_some` operation.
+fn push_keys(
+    keys: &Expression,
+    kind: ReferenceKind,
+    location: &str,
+    depth: usize,
+    bound: &[&str],
+    refs: &mut Vec<VariableReference>,
+) {
+    let paths = match keys {
+        Expression::Constant(Value::Array(keys)) => keys.iter().map(constant_path).collect(),
+        Expression::Constant(key) => vec![constant_path(key)],
+        _ => vec![VariablePath::Dynamic(keys.to_json())],
+    };
+
+    let paths = paths.into_iter().filter(|path| !is_bound(path, bound));
+    refs.extend(paths.map(|path| {
+        let (path, scoped) = unscope(path, depth);
+        VariableReference {
+            path,
+            default: None,
+            kind,
+            scoped,
+            location: location.to_owned(),
+        }
+    }));
+}
+
+/// Strips the `$root.` and `../` prefixes of a static path that is nested in `depth` array element
+/// scopes, see `Data`. Returns the path in the scope it refers to, and whether that scope is an
+/// array element. Paths that refer beyond the data are kept as they are.
+fn unscope(path: VariablePath, depth: usize) -> (VariablePath, bool) {
+    let stripped = match &path {
+        VariablePath::Static(static_path) => strip_scope(static_path, depth),
+        VariablePath::Dynamic(_) => None,
+    };
+    match stripped {
+        Some((rest, depth)) => (VariablePath::Static(rest.to_owned()), depth > 0),
+        None => (path, depth > 0),
+    }
+}
+
+/// Strips the `$root.` and `../` prefixes of a path that is nested in `depth` array element scopes.
+/// Returns the path in the scope it refers to and the number of array element scopes that scope is
+/// nested in, i.e. 0 if the path refers to the data. Returns `None` if the path refers beyond the
+/// data.
+pub(crate) fn strip_scope(path: &str, depth: usize) -> Option<(&str, usize)> {
+    if let Some(rest) = path.strip_prefix(data::ROOT) {
+        if rest.is_empty() || rest.starts_with('.') {
+            return Some((rest.get(1..).unwrap_or(""), 0));
+        }
+    }
+
+    let mut rest = path;
+    let mut parents = 0;
+    while let Some(stripped) = rest.strip_prefix(data::PARENT) {
+        parents += 1;
+        rest = stripped;
+    }
+    if rest == ".." {
+        parents += 1;
+        rest = "";
+    }
+    depth.checked_sub(parents).map(|depth| (rest, depth))
+}
+
+/// Returns whether the first step of a static path without a scope prefix is one of the names bound
+/// by enclosing `let` operations.
+fn is_bound(path: &VariablePath, bound: &[&str]) -> bool {
+    match path {
+        VariablePath::Static(path) => {
+            let name = path.split('.').next().unwrap_or("");
+            bound.contains(&name)
+        }
+        VariablePath::Dynamic(_) => false,
+    }
+}
+
+fn variable_path(arg: &Expression) -> VariablePath {
+    match arg {
+        Expression::Constant(value) => constant_path(value),
+        _ => VariablePath::Dynamic(arg.to_json()),
+    }
+}
+
+fn constant_path(value: &Value) -> VariablePath {
+    match value {
+        Value::Null => VariablePath::Static(String::new()),
+        Value::String(path) => VariablePath::Static(path.clone()),
+        Value::Number(_) => VariablePath::Static(value.to_string()),
+        Value::Bool(_) | Value::Array(_) | Value::Object(_) => VariablePath::Dynamic(value.clone()),
+    }
+}
+
+/// Returns whether the second argument of the operator is applied to the elements of the array
+/// given as the first argument.
+pub(crate) fn is_array_operator(operator: Operator) -> bool {
+    matches!(
+        operator,
+        Operator::Map
+            | Operator::Filter
+            | Operator::Reduce
+            | Operator::All
+            | Operator::Some
+            | Operator::None
+    )
+}
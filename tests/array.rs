@@ -266,3 +266,103 @@ fn none() {
     let rule = json!({ "none": ["bbb", { "===": [{ "var": "" }, "a"] }] });
     assert_eq!(apply(&rule, &json!(null)), Ok(json!(true)));
 }
+
+#[test]
+fn outer_scope() {
+    let data = json!({
+        "currency": "EUR",
+        "limit": 2,
+        "items": [
+            { "currency": "EUR", "tags": ["a", "b"] },
+            { "currency": "EUR", "tags": ["c"] }
+        ]
+    });
+
+    // Every line item's currency equals the order currency.
+    let rule = json!({
+        "all": [{ "var": "items" }, { "==": [{ "var": "currency" }, { "var": "../currency" }] }]
+    });
+    assert_eq!(apply(&rule, &data), Ok(json!(true)));
+    let rule = json!({
+        "all": [{ "var": "items" }, { "==": [{ "var": "currency" }, { "var": "$root.currency" }] }]
+    });
+    assert_eq!(apply(&rule, &data), Ok(json!(true)));
+    assert_eq!(
+        apply(&rule, &json!({ "currency": "USD", "items": data["items"] })),
+        Ok(json!(false))
+    );
+
+    // Nested scopes, the whole data of a scope and the index.
+    assert_eq!(
+        apply(
+            &json!({ "map": [{ "var": "items" }, { "map": [{ "var": "tags" }, {
+                "cat": [{ "var": "" }, { "var": "../$index" }, { "var": "$index" }, { "var": "../../currency" }]
+            }] }] }),
+            &data
+        ),
+        Ok(json!([["a00EUR", "b01EUR"], ["c10EUR"]]))
+    );
+    assert_eq!(
+        apply(
+            &json!({ "map": [[1], { "var": ".." }] }),
+            &json!({ "a": 1 })
+        ),
+        Ok(json!([{ "a": 1 }]))
+    );
+    assert_eq!(
+        apply(
+            &json!({ "map": [[1], { "var": "$root" }] }),
+            &json!({ "a": 1 })
+        ),
+        Ok(json!([{ "a": 1 }]))
+    );
+    assert_eq!(
+        apply(
+            &json!({ "filter": [{ "var": "items" }, { "<": [{ "var": "$index" }, { "var": "$root.limit" }] }] }),
+            &json!({ "limit": 1, "items": ["x", "y"] })
+        ),
+        Ok(json!(["x"]))
+    );
+    assert_eq!(
+        apply(
+            &json!({ "some": [[1, 2], { "missing": ["../a", "../b"] }] }),
+            &json!({ "a": 1 })
+        ),
+        Ok(json!(true))
+    );
+
+    // Beyond the outermost scope, or outside of arrays.
+    assert_eq!(
+        apply(
+            &json!({ "map": [[1], { "var": ["../../a", "none"] }] }),
+            &json!({ "a": 1 })
+        ),
+        Ok(json!(["none"]))
+    );
+    assert_eq!(
+        apply(&json!({ "var": "$root.a" }), &json!({ "a": 1 })),
+        Ok(json!(1))
+    );
+    assert_eq!(
+        apply(&json!({ "var": "$index" }), &json!({ "$index": 1 })),
+        Ok(json!(1))
+    );
+}
+
+#[test]
+fn reduce_outer_scope() {
+    let rule = json!({
+        "reduce": [
+            { "var": "items" },
+            { "+": [
+                { "var": "accumulator" },
+                { "*": [{ "var": "current" }, { "var": "../factor" }, { "var": "$index" }] }
+            ] },
+            0
+        ]
+    });
+    assert_eq!(
+        apply(&rule, &json!({ "factor": 10, "items": [5, 6, 7] })),
+        Ok(json!(200.0))
+    );
+}
@@ -0,0 +1,125 @@
+use std::sync::Arc;
+use std::thread;
+
+use jsonlogic::expression::Expression;
+use jsonlogic::{apply, partial_apply, CompiledRule, PartialApplyOutcome, Rule};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+#[test]
+fn evaluate_many_times() {
+    let logic = json!({
+        "if": [
+            {"==": [{ "%": [{ "var": "i" }, 15] }, 0]},
+            "fizzbuzz",
+            {"==": [{ "%": [{ "var": "i" }, 3] }, 0]},
+            "fizz",
+            {"==": [{ "%": [{ "var": "i" }, 5] }, 0]},
+            "buzz",
+            { "var": "i" }
+        ]
+    });
+    let rule = CompiledRule::new(&logic).unwrap();
+
+    for i in 1..=30 {
+        let data = json!({ "i": i });
+        assert_eq!(rule.evaluate(&data), apply(&logic, &data));
+    }
+}
+
+#[test]
+fn partial_evaluate() {
+    let logic = json!({
+        "and": [
+            { "==": [{ "var": "status" }, "student"] },
+            { ">": [{ "var": "age" }, 18] }
+        ]
+    });
+    let rule = CompiledRule::new(&logic).unwrap();
+
+    assert_eq!(
+        rule.partial_evaluate(&json!({ "status": "student" })),
+        Ok(PartialApplyOutcome::Ambiguous)
+    );
+    assert_eq!(
+        rule.partial_evaluate(&json!({ "status": "employed" })),
+        Ok(PartialApplyOutcome::Resolved(json!(false)))
+    );
+    assert_eq!(
+        rule.partial_evaluate(&json!({ "status": "student", "age": 20 })),
+        partial_apply(&logic, &json!({ "status": "student", "age": 20 }))
+    );
+}
+
+#[test]
+fn outlives_source_json() {
+    let rule = {
+        let logic = json!({ "cat": ["Hello, ", { "var": "name" }] });
+        CompiledRule::new(&logic).unwrap()
+    };
+
+    assert_eq!(
+        rule.evaluate(&json!({ "name": "Bob" })),
+        Ok(json!("Hello, Bob"))
+    );
+    assert_eq!(rule.evaluate(&Value::Null), Ok(json!("Hello, null")));
+}
+
+#[test]
+fn shared_between_threads() {
+    let rule = Arc::new(CompiledRule::new(&json!({ "*": [{ "var": "n" }, 2] })).unwrap());
+
+    let handles: Vec<_> = (0..4)
+        .map(|n| {
+            let rule = Arc::clone(&rule);
+            thread::spawn(move || rule.evaluate(&json!({ "n": n })))
+        })
+        .collect();
+
+    let results: Vec<Value> = handles
+        .into_iter()
+        .map(|h| h.join().unwrap().unwrap())
+        .collect();
+    assert_eq!(
+        results,
+        vec![json!(0.0), json!(2.0), json!(4.0), json!(6.0)]
+    );
+}
+
+#[test]
+fn unknown_operator() {
+    assert!(CompiledRule::new(&json!({ "foo": [1, 2] })).is_err());
+}
+
+#[test]
+fn serde() {
+    #[derive(Deserialize, Serialize)]
+    struct Route {
+        condition: Rule,
+        target: String,
+    }
+
+    let config = r#"{ "condition": { "==": [{ "var": "tier" }, "gold"] }, "target": "priority" }"#;
+    let route: Route = serde_json::from_str(config).unwrap();
+    assert_eq!(
+        route.condition.evaluate(&json!({ "tier": "gold" })),
+        Ok(json!(true))
+    );
+    assert_eq!(
+        serde_json::to_value(&route).unwrap(),
+        json!({
+            "condition": { "==": [{ "var": ["tier"] }, "gold"] },
+            "target": "priority"
+        })
+    );
+
+    let config = r#"{ "condition": { "match": ["a", "("] }, "target": "priority" }"#;
+    let error = serde_json::from_str::<Route>(config).err().unwrap();
+    assert!(error.to_string().starts_with("Invalid regex ("));
+
+    let expression: Expression = serde_json::from_value(json!({ "!": { "var": "a" } })).unwrap();
+    assert_eq!(
+        serde_json::to_value(&expression).unwrap(),
+        json!({ "!": [{ "var": ["a"] }] })
+    );
+}
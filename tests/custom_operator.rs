@@ -0,0 +1,205 @@
+use jsonlogic::data::Data;
+use jsonlogic::expression::Expression;
+use jsonlogic::registry::{Arity, CustomOperator, OperatorRegistry};
+use jsonlogic::{Error, Evaluator, PartialApplyOutcome};
+use serde_json::{json, Value};
+
+/// Returns the first argument that is not null, without evaluating the remaining ones.
+struct Coalesce;
+
+impl CustomOperator for Coalesce {
+    fn compute(&self, args: &[Expression], data: &Data) -> Value {
+        args.iter()
+            .enumerate()
+            .map(|(i, arg)| arg.compute(&data.argument(i)))
+            .find(|value| !value.is_null())
+            .unwrap_or(Value::Null)
+    }
+}
+
+/// Returns the length of a string or an array.
+struct Length;
+
+impl CustomOperator for Length {
+    fn compute(&self, args: &[Expression], data: &Data) -> Value {
+        match args[0].compute(&data.argument(0)) {
+            Value::String(s) => json!(s.chars().count()),
+            Value::Array(arr) => json!(arr.len()),
+            _ => Value::Null,
+        }
+    }
+
+    fn arity(&self) -> Option<Arity> {
+        Some(Arity::exact(1))
+    }
+}
+
+fn evaluator() -> Evaluator {
+    let mut registry = OperatorRegistry::new();
+    registry.register("coalesce", Coalesce).unwrap();
+    registry.register("length", Length).unwrap();
+    Evaluator::with_registry(registry)
+}
+
+#[test]
+fn compute() {
+    let evaluator = evaluator();
+    let rule = json!({ "coalesce": [{ "var": "nickname" }, { "var": "name" }, "anonymous"] });
+
+    assert_eq!(
+        evaluator.apply(&rule, &json!({ "nickname": "bobby", "name": "Bob" })),
+        Ok(json!("bobby"))
+    );
+    assert_eq!(
+        evaluator.apply(&rule, &json!({ "name": "Bob" })),
+        Ok(json!("Bob"))
+    );
+    assert_eq!(evaluator.apply(&rule, &Value::Null), Ok(json!("anonymous")));
+
+    // Custom operators can be nested in built-in operators and the other way around.
+    assert_eq!(
+        evaluator.apply(
+            &json!({ ">": [{ "length": { "cat": [{ "var": "a" }, "bc"] } }, 2] }),
+            &json!({ "a": "a" })
+        ),
+        Ok(json!(true))
+    );
+}
+
+#[test]
+fn unknown_without_registry() {
+    let rule = json!({ "length": "abc" });
+    assert_eq!(
+        jsonlogic::apply(&rule, &Value::Null),
+        Err(Error::UnknownOperator {
+            name: String::from("length"),
+            path: String::from("$")
+        })
+    );
+    assert_eq!(evaluator().apply(&rule, &Value::Null), Ok(json!(3)));
+}
+
+#[test]
+fn arity() {
+    let evaluator = evaluator();
+    assert_eq!(
+        evaluator.apply(&json!({ "length": ["abc", "def"] }), &Value::Null),
+        Err(Error::InvalidArguments {
+            operator: String::from("length"),
+            message: String::from("expects 1 arguments, got 2"),
+            path: String::from("$")
+        })
+    );
+    assert!(evaluator
+        .apply(&json!({ "length": [] }), &Value::Null)
+        .is_err());
+
+    assert!(Arity::range(2, 3).contains(2));
+    assert!(!Arity::range(2, 3).contains(4));
+    assert!(Arity::at_least(1).contains(100));
+}
+
+#[test]
+fn builtin_cannot_be_overridden() {
+    let mut evaluator = Evaluator::new();
+    assert_eq!(
+        evaluator.register("==", Coalesce),
+        Err(Error::ReservedOperator {
+            name: String::from("==")
+        })
+    );
+}
+
+#[test]
+fn partial() {
+    let evaluator = evaluator();
+    let rule = json!({ "==": [{ "length": { "var": "name" } }, 3] });
+
+    assert_eq!(
+        evaluator.partial_apply(&rule, &json!({ "name": "Bob" })),
+        Ok(PartialApplyOutcome::Resolved(json!(true)))
+    );
+    assert_eq!(
+        evaluator.partial_apply(&rule, &Value::Null),
+        Ok(PartialApplyOutcome::Ambiguous)
+    );
+    assert_eq!(
+        evaluator.partial_apply_residual(
+            &json!({ "and": [{ "var": "active" }, rule] }),
+            &json!({ "active": true })
+        ),
+        Ok(PartialApplyOutcome::Residual(
+            json!({ "==": [{ "length": [{ "var": ["name"] }] }, 3] })
+        ))
+    );
+}
+
+#[test]
+fn compiled_rule() {
+    let rule = evaluator()
+        .compile(&json!({ "length": { "var": "items" } }))
+        .unwrap();
+
+    assert_eq!(rule.evaluate(&json!({ "items": [1, 2, 3] })), Ok(json!(3)));
+    assert_eq!(rule.evaluate(&json!({ "items": "ab" })), Ok(json!(2)));
+}
+
+#[test]
+fn error_path() {
+    let err = evaluator()
+        .apply(
+            &json!({ "and": [true, { "or": [{ "length": [] }, false] }] }),
+            &Value::Null,
+        )
+        .unwrap_err();
+    assert_eq!(err.path(), Some("$.and[1].or[0]"));
+}
+
+#[test]
+fn trace() {
+    let (result, trace) = evaluator()
+        .apply_with_trace(
+            &json!({ "coalesce": [null, { "length": [{ "var": "name" }] }, 0] }),
+            &json!({ "name": "Bob" }),
+        )
+        .unwrap();
+
+    assert_eq!(result, json!(3));
+    let root = trace.root.unwrap();
+    assert_eq!(
+        root.arguments,
+        vec![Some(Value::Null), Some(json!(3)), None]
+    );
+    assert_eq!(root.children[0].path, "$.coalesce[1]");
+    assert_eq!(root.children[0].children[0].path, "$.coalesce[1].length[0]");
+
+    /// Sums clones of its arguments, like operators that rebuild their arguments.
+    struct ClonedSum;
+
+    impl CustomOperator for ClonedSum {
+        fn compute(&self, args: &[Expression], data: &Data) -> Value {
+            let args = args.to_vec();
+            let sum = args
+                .iter()
+                .enumerate()
+                .filter_map(|(i, arg)| arg.compute(&data.argument(i)).as_f64())
+                .sum::<f64>();
+            json!(sum)
+        }
+    }
+
+    let mut evaluator = Evaluator::new();
+    evaluator.register("cloned_sum", ClonedSum).unwrap();
+    let (result, trace) = evaluator
+        .apply_with_trace(&json!({ "cloned_sum": [1, { "+": [2, 3] }] }), &Value::Null)
+        .unwrap();
+
+    assert_eq!(result, json!(6.0));
+    let root = trace.root.unwrap();
+    assert_eq!(root.arguments, vec![Some(json!(1)), Some(json!(5.0))]);
+    assert_eq!(root.children[0].path, "$.cloned_sum[1]");
+    assert_eq!(
+        root.children[0].arguments,
+        vec![Some(json!(2)), Some(json!(3))]
+    );
+}
@@ -0,0 +1,159 @@
+use jsonlogic::data_source::{Layered, LazySource};
+use jsonlogic::{apply, partial_apply, CompiledRule, PartialApplyOutcome};
+use serde_json::{json, Value};
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+fn hash_map() -> HashMap<String, Value> {
+    let mut map = HashMap::new();
+    map.insert(
+        String::from("user"),
+        json!({ "name": "bob", "tags": ["a", "b"] }),
+    );
+    map.insert(String::from("amount"), json!(100));
+    map
+}
+
+#[test]
+fn hash_map_source() {
+    let data = hash_map();
+
+    assert_eq!(apply(&json!({ "var": "amount" }), &data), Ok(json!(100)));
+    assert_eq!(
+        apply(&json!({ "var": "user.name" }), &data),
+        Ok(json!("bob"))
+    );
+    assert_eq!(
+        apply(&json!({ "var": "user.tags.1" }), &data),
+        Ok(json!("b"))
+    );
+    assert_eq!(
+        apply(&json!({ "var": ["user.age", 18] }), &data),
+        Ok(json!(18))
+    );
+    assert_eq!(
+        apply(&json!({ "var": "" }), &data),
+        Ok(json!({ "user": { "name": "bob", "tags": ["a", "b"] }, "amount": 100 }))
+    );
+    assert_eq!(
+        apply(
+            &json!({ "missing": ["amount", "user.name", "user.age"] }),
+            &data
+        ),
+        Ok(json!(["user.age"]))
+    );
+    assert_eq!(
+        apply(&json!({ "missing_some": [1, ["amount", "fee"]] }), &data),
+        Ok(json!([]))
+    );
+}
+
+#[test]
+fn closure_source() {
+    let data = |path: &str| match path {
+        "a" => Some(json!(1)),
+        "b.c" => Some(json!([1, 2, 3])),
+        "0" => Some(json!("zero")),
+        _ => None,
+    };
+
+    assert_eq!(
+        apply(&json!({ "+": [{ "var": "a" }, 2] }), &data),
+        Ok(json!(3.0))
+    );
+    assert_eq!(
+        apply(&json!({ "in": [2, { "var": "b.c" }] }), &data),
+        Ok(json!(true))
+    );
+    assert_eq!(apply(&json!({ "var": 0 }), &data), Ok(json!("zero")));
+    assert_eq!(apply(&json!({ "var": "" }), &data), Ok(Value::Null));
+    assert_eq!(
+        apply(&json!({ "missing": ["a", "x"] }), &data),
+        Ok(json!(["x"]))
+    );
+    assert_eq!(
+        partial_apply(&json!({ "==": [{ "var": "x" }, 1] }), &data),
+        Ok(PartialApplyOutcome::Ambiguous)
+    );
+
+    // Values of array elements are scoped to the element, not resolved through the source.
+    assert_eq!(
+        apply(
+            &json!({ "map": [{ "var": "b.c" }, { "*": [{ "var": "" }, 2] }] }),
+            &data
+        ),
+        Ok(json!([2.0, 4.0, 6.0]))
+    );
+}
+
+#[test]
+fn layered_source() {
+    let request = json!({ "amount": 250, "currency": "EUR" });
+    let merchant = hash_map();
+    let defaults = |path: &str| match path {
+        "fee" => Some(json!(0.5)),
+        "amount" => Some(json!(0)),
+        _ => None,
+    };
+    let data = Layered::new(vec![&request, &merchant, &defaults]);
+
+    let rule = CompiledRule::new(&json!({
+        "cat": [{ "var": "user.name" }, ":", { "var": "amount" }, ":", { "var": "fee" }]
+    }))
+    .unwrap();
+    assert_eq!(rule.evaluate(&data), Ok(json!("bob:250:0.5")));
+    assert_eq!(
+        apply(
+            &json!({ "missing": ["currency", "fee", "user.age"] }),
+            &data
+        ),
+        Ok(json!(["user.age"]))
+    );
+    // Merges the objects of the layers that have one, the first layer takes precedence.
+    assert_eq!(
+        apply(&json!({ "var": "" }), &data),
+        Ok(json!({
+            "amount": 250,
+            "currency": "EUR",
+            "user": { "name": "bob", "tags": ["a", "b"] }
+        }))
+    );
+}
+
+#[test]
+fn lazy_source() {
+    let calls = RefCell::new(vec![]);
+    let data = LazySource::new(|path: &str| {
+        calls.borrow_mut().push(path.to_owned());
+        match path {
+            "country" => Some(json!("DE")),
+            "score" => Some(json!(80)),
+            "blocked" => Some(json!(false)),
+            _ => None,
+        }
+    });
+
+    let rule = json!({
+        "if": [
+            { "==": [{ "var": "country" }, "US"] },
+            { "var": "us_limit" },
+            { "and": [
+                { "!": { "var": "blocked" } },
+                { ">": [{ "var": "score" }, 50] },
+                { "<": [{ "var": "score" }, 100] },
+                { "missing": ["country", "email"] }
+            ] }
+        ]
+    });
+    assert_eq!(apply(&rule, &data), Ok(json!(["email"])));
+    // Short-circuited paths are not fetched, and every path is fetched once.
+    assert_eq!(
+        data.fetched_paths(),
+        vec!["country", "blocked", "score", "email"]
+    );
+    assert_eq!(*calls.borrow(), data.fetched_paths());
+
+    // Values are cached across evaluations with the same source.
+    assert_eq!(apply(&json!({ "var": "score" }), &data), Ok(json!(80)));
+    assert_eq!(calls.borrow().len(), 4);
+}
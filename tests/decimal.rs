@@ -0,0 +1,133 @@
+#![cfg(feature = "decimal")]
+
+use jsonlogic::{apply, Evaluator};
+use serde_json::{json, Value};
+
+fn decimal_evaluator() -> Evaluator {
+    let mut evaluator = Evaluator::new();
+    evaluator.set_decimal_arithmetic(true);
+    evaluator
+}
+
+#[test]
+fn decimal_arithmetic() {
+    let evaluator = decimal_evaluator();
+    let apply = |rule: Value, data: Value| evaluator.apply(&rule, &data);
+
+    assert_eq!(apply(json!({"*": [0.1, 3]}), Value::Null), Ok(json!(0.3)));
+    assert_eq!(apply(json!({"+": [0.1, 0.2]}), Value::Null), Ok(json!(0.3)));
+    assert_eq!(apply(json!({"-": [1, 0.9]}), Value::Null), Ok(json!(0.1)));
+    assert_eq!(apply(json!({"-": [0.5]}), Value::Null), Ok(json!(-0.5)));
+    assert_eq!(apply(json!({"/": [1, 4]}), Value::Null), Ok(json!(0.25)));
+    assert_eq!(
+        apply(json!({"/": [1, 3]}), Value::Null),
+        Ok(json!(1.0 / 3.0))
+    );
+    assert_eq!(apply(json!({"/": [1, 0]}), Value::Null), Ok(Value::Null));
+    assert_eq!(apply(json!({"%": [5.5, 2]}), Value::Null), Ok(json!(1.5)));
+    assert_eq!(
+        apply(json!({"min": [0.3, 0.1]}), Value::Null),
+        Ok(json!(0.1))
+    );
+    assert_eq!(
+        apply(json!({"max": [0.3, "0.5"]}), Value::Null),
+        Ok(json!(0.5))
+    );
+    assert_eq!(
+        apply(
+            json!({"+": [{"var": "price"}, {"var": "tax"}]}),
+            json!({"price": "12.10", "tax": "2.20"})
+        ),
+        Ok(json!(14.3))
+    );
+
+    // Operands that are no decimals are computed with floats.
+    assert_eq!(
+        apply(json!({"+": ["1.5px", 1]}), Value::Null),
+        Ok(json!(2.5))
+    );
+    assert_eq!(
+        apply(json!({"*": [1e300, 1e300]}), Value::Null),
+        Ok(Value::Null)
+    );
+}
+
+#[test]
+fn float_arithmetic_by_default() {
+    assert_ne!(apply(&json!({"*": [0.1, 3]}), &Value::Null), Ok(json!(0.3)));
+
+    let mut evaluator = decimal_evaluator();
+    evaluator.set_integer_arithmetic(true);
+    assert_ne!(
+        evaluator.apply(&json!({"*": [0.1, 3]}), &Value::Null),
+        Ok(json!(0.3))
+    );
+}
+
+// Results that do not fit into 28 fractional digits are computed with floats instead of rounded.
+#[test]
+fn inexact_results() {
+    let evaluator = decimal_evaluator();
+    let apply = |rule: Value| evaluator.apply(&rule, &Value::Null);
+
+    assert_eq!(apply(json!({"*": [1e-20, 1e-20]})), Ok(json!(1e-40)));
+    assert_eq!(apply(json!({"/": [1e-20, 1e20]})), Ok(json!(1e-40)));
+    assert_eq!(apply(json!({"+": [1e20, 1e-20]})), Ok(json!(1e20)));
+    assert_eq!(
+        apply(json!({"<": [0, {"*": [1e-20, 1e-20]}]})),
+        Ok(json!(true))
+    );
+}
+
+#[test]
+fn exact_comparisons() {
+    let a = json!(0.1);
+    let b: Value = serde_json::from_str("0.10000000000000000001").unwrap();
+
+    assert_eq!(apply(&json!({"<": [a, b]}), &Value::Null), Ok(json!(true)));
+    assert_eq!(
+        apply(&json!({"==": [a, b]}), &Value::Null),
+        Ok(json!(false))
+    );
+    assert_eq!(
+        apply(&json!({"==": [a, "0.10"]}), &Value::Null),
+        Ok(json!(true))
+    );
+    assert_eq!(
+        apply(&json!({"===": [1.0, 1]}), &Value::Null),
+        Ok(json!(true))
+    );
+}
+
+// Like in javascript, numbers beyond the range of floats are infinite.
+#[test]
+fn numbers_beyond_floats() {
+    let huge: Value = serde_json::from_str("1e400").unwrap();
+    let negative: Value = serde_json::from_str("-1e400").unwrap();
+
+    assert_eq!(apply(&json!({"!!": [huge]}), &Value::Null), Ok(json!(true)));
+    assert_eq!(
+        apply(&json!({"+": [huge, 2]}), &Value::Null),
+        Ok(Value::Null)
+    );
+    assert_eq!(
+        apply(&json!({"==": [huge, "1e400"]}), &Value::Null),
+        Ok(json!(true))
+    );
+    assert_eq!(
+        apply(&json!({"==": [huge, huge]}), &Value::Null),
+        Ok(json!(true))
+    );
+    assert_eq!(
+        apply(&json!({"===": [huge, huge]}), &Value::Null),
+        Ok(json!(true))
+    );
+    assert_eq!(
+        apply(&json!({"<": [negative, -1e308, 1e308]}), &Value::Null),
+        Ok(json!(true))
+    );
+    assert_eq!(
+        apply(&json!({"<": [1e308, huge]}), &Value::Null),
+        Ok(json!(true))
+    );
+}
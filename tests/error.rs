@@ -0,0 +1,37 @@
+use jsonlogic::{apply, CompiledRule, Error};
+use serde_json::{json, Value};
+
+#[test]
+fn unknown_operator() {
+    assert_eq!(
+        apply(&json!({ "foo": [1, 2] }), &Value::Null),
+        Err(Error::UnknownOperator {
+            name: String::from("foo"),
+            path: String::from("$")
+        })
+    );
+
+    let logic = json!({
+        "and": [
+            { "==": [{ "var": "a" }, 1] },
+            { "!": { "bar": [] } }
+        ]
+    });
+    assert_eq!(
+        CompiledRule::new(&logic),
+        Err(Error::UnknownOperator {
+            name: String::from("bar"),
+            path: String::from("$.and[1]['!']")
+        })
+    );
+}
+
+#[test]
+fn display() {
+    let err = apply(&json!({ "if": [true, { "foo": 1 }] }), &Value::Null).unwrap_err();
+    assert_eq!(err.to_string(), "Unrecognized operation foo at $.if[1]");
+
+    // Usable as a boxed std error.
+    let boxed: Box<dyn std::error::Error> = Box::new(err);
+    assert_eq!(boxed.to_string(), "Unrecognized operation foo at $.if[1]");
+}
@@ -0,0 +1,136 @@
+use jsonlogic::{
+    apply, partial_apply, partial_apply_residual, Error,
+    PartialApplyOutcome::{Ambiguous, Residual, Resolved},
+};
+use serde_json::{json, Value};
+
+#[test]
+fn binds_names() {
+    assert_eq!(
+        apply(
+            &json!({ "let": ["total", { "*": [{ "var": "price" }, { "var": "qty" }] },
+                { "if": [{ ">": [{ "var": "total" }, 100] }, { "var": "total" }, 0] }] }),
+            &json!({ "price": 30, "qty": 4 })
+        ),
+        Ok(json!(120.0))
+    );
+    assert_eq!(
+        apply(
+            &json!({ "let": [{ "cat": ["no", "bindings"] }] }),
+            &Value::Null
+        ),
+        Ok(json!("nobindings"))
+    );
+}
+
+#[test]
+fn sequential() {
+    let logic = json!({ "let": [
+        "net", { "-": [{ "var": "gross" }, { "var": "discount" }] },
+        "tax", { "*": [{ "var": "net" }, 0.5] },
+        { "+": [{ "var": "net" }, { "var": "tax" }] }
+    ] });
+    assert_eq!(
+        apply(&logic, &json!({ "gross": 30, "discount": 10 })),
+        Ok(json!(30.0))
+    );
+}
+
+#[test]
+fn shadows_data() {
+    let logic = json!({ "let": ["a", 1, "a", { "+": [{ "var": "a" }, 1] }, { "var": "a" }] });
+    assert_eq!(apply(&logic, &json!({ "a": 10 })), Ok(json!(2.0)));
+
+    // A path with further steps is resolved in the bound value.
+    let logic = json!({ "let": ["user", { "var": "customer" }, { "var": "user.name" }] });
+    assert_eq!(
+        apply(
+            &logic,
+            &json!({ "customer": { "name": "Ann" }, "user": { "name": "Bob" } })
+        ),
+        Ok(json!("Ann"))
+    );
+}
+
+#[test]
+fn array_operations() {
+    // The name is visible in the elements, unless an element has a value with that name.
+    let logic = json!({ "let": ["limit", { "var": "limit" },
+        { "filter": [{ "var": "items" }, { ">": [{ "var": "price" }, { "var": "limit" }] }] }] });
+    assert_eq!(
+        apply(
+            &logic,
+            &json!({ "limit": 5, "items": [{ "price": 3 }, { "price": 8 }, { "price": 6, "limit": 7 }] })
+        ),
+        Ok(json!([{ "price": 8 }]))
+    );
+
+    // Inside the elements, `../` refers to the data the `let` is evaluated against.
+    let logic = json!({ "map": [[1, 2], { "let": ["x", 10,
+        { "+": [{ "var": "x" }, { "var": "" }, { "var": "../offset" }] }] }] });
+    assert_eq!(
+        apply(&logic, &json!({ "offset": 100 })),
+        Ok(json!([111.0, 112.0]))
+    );
+}
+
+#[test]
+fn partial() {
+    let logic = json!({ "let": ["age", { "var": "age" }, "limit", 18,
+        { ">=": [{ "var": "age" }, { "var": "limit" }] }] });
+    assert_eq!(
+        partial_apply(&logic, &json!({ "age": 20 })),
+        Ok(Resolved(json!(true)))
+    );
+    assert_eq!(partial_apply(&logic, &Value::Null), Ok(Ambiguous));
+
+    // An unknown binding does not fall back to the default or the data.
+    let logic = json!({ "let": ["a", { "var": "b" }, { "var": ["a", 0] }] });
+    assert_eq!(partial_apply(&logic, &json!({ "a": 1 })), Ok(Ambiguous));
+    let logic = json!({ "let": ["a", { "var": "b" }, { "missing": ["a"] }] });
+    assert_eq!(partial_apply(&logic, &json!({ "a": 1 })), Ok(Ambiguous));
+
+    // Bindings that the body does not use do not matter.
+    let logic = json!({ "let": ["a", { "var": "b" }, { "var": "c" }] });
+    assert_eq!(
+        partial_apply(&logic, &json!({ "c": 1 })),
+        Ok(Resolved(json!(1)))
+    );
+}
+
+#[test]
+fn residual() {
+    let logic = json!({ "let": ["total", { "+": [{ "var": "a" }, { "var": "b" }] },
+        { "and": [{ "var": "enabled" }, { ">": [{ "var": "total" }, 10] }] }] });
+    assert_eq!(
+        partial_apply_residual(&logic, &json!({ "a": 5, "b": 6 })),
+        Ok(Residual(json!({ "let": ["total", 11.0,
+            { "and": [{ "var": ["enabled"] }, true] }] })))
+    );
+    assert_eq!(
+        partial_apply_residual(&logic, &json!({ "enabled": true, "a": 5 })),
+        Ok(Residual(
+            json!({ "let": ["total", { "+": [5, { "var": ["b"] }] },
+            { ">": [{ "var": ["total"] }, 10] }] })
+        ))
+    );
+    assert_eq!(
+        partial_apply_residual(&logic, &json!({ "enabled": false })),
+        Ok(Resolved(json!(false)))
+    );
+}
+
+#[test]
+fn invalid_bindings() {
+    for logic in &[
+        json!({ "let": [] }),
+        json!({ "let": ["a", 1] }),
+        json!({ "let": [1, 1, { "var": "a" }] }),
+        json!({ "let": [{ "var": "name" }, 1, { "var": "a" }] }),
+    ] {
+        assert!(matches!(
+            apply(logic, &Value::Null),
+            Err(Error::InvalidArguments { .. })
+        ));
+    }
+}
@@ -0,0 +1,184 @@
+use jsonlogic::expression::Expression;
+use jsonlogic::limits::{Limits, DEFAULT_MAX_DEPTH};
+use jsonlogic::validation::{validate, ValidationConfig, ValidationError};
+use jsonlogic::{Error, Evaluator, PartialApplyOutcome};
+use serde_json::{json, Value};
+
+fn evaluator(limits: Limits) -> Evaluator {
+    let mut evaluator = Evaluator::new();
+    evaluator.set_limits(limits);
+    evaluator
+}
+
+fn limit_exceeded(limit: &str, path: &str) -> Error {
+    Error::LimitExceeded {
+        limit: String::from(limit),
+        path: String::from(path),
+    }
+}
+
+#[test]
+fn steps() {
+    let evaluator = evaluator(Limits {
+        max_steps: Some(20),
+        ..Default::default()
+    });
+    let rule = json!({ "map": [{ "var": "items" }, { "*": [{ "var": "" }, 2] }] });
+
+    assert_eq!(
+        evaluator.apply(&rule, &json!({ "items": [1, 2, 3] })),
+        Ok(json!([2.0, 4.0, 6.0]))
+    );
+    assert_eq!(
+        evaluator.apply(&rule, &json!({ "items": (0..10).collect::<Vec<_>>() })),
+        Err(limit_exceeded("max_steps", "$.map[1]"))
+    );
+    assert_eq!(
+        evaluator.partial_apply(&rule, &json!({ "items": (0..10).collect::<Vec<_>>() })),
+        Err(limit_exceeded("max_steps", "$.map[1]"))
+    );
+}
+
+#[test]
+fn steps_bound_growing_reduce() {
+    let evaluator = evaluator(Limits {
+        max_steps: Some(1_000),
+        ..Default::default()
+    });
+    let items: Vec<usize> = (0..5_000).collect();
+    let rule = json!({
+        "reduce": [
+            { "var": "items" },
+            { "merge": [{ "var": "accumulator" }, [{ "var": "current" }]] },
+            []
+        ]
+    });
+
+    assert_eq!(
+        evaluator.apply(&rule, &json!({ "items": items })),
+        Err(limit_exceeded("max_steps", "$.reduce[1]"))
+    );
+}
+
+#[test]
+fn depth() {
+    let evaluator = evaluator(Limits {
+        max_depth: Some(64),
+        ..Default::default()
+    });
+
+    let mut rule = json!(true);
+    for _ in 0..1_000 {
+        rule = json!({ "!": rule });
+    }
+    assert_eq!(
+        evaluator.compile(&rule).unwrap_err(),
+        limit_exceeded("max_depth", &format!("${}", "['!']".repeat(64)))
+    );
+    assert_eq!(
+        evaluator.apply(&json!({ "!": { "!": true } }), &Value::Null),
+        Ok(json!(true))
+    );
+}
+
+#[test]
+fn size() {
+    let evaluator = evaluator(Limits {
+        max_size: Some(5),
+        ..Default::default()
+    });
+    let rule = json!({ "if": [true, { "cat": [{ "var": "a" }, { "var": "b" }] }] });
+
+    assert_eq!(
+        evaluator.apply(&rule, &json!({ "a": "ab", "b": "cd" })),
+        Ok(json!("abcd"))
+    );
+    assert_eq!(
+        evaluator.apply(&rule, &json!({ "a": "abc", "b": "def" })),
+        Err(limit_exceeded("max_size", "$.if[1]"))
+    );
+    assert_eq!(
+        evaluator.apply(
+            &json!({ "merge": [{ "var": "items" }, 7] }),
+            &json!({ "items": [1, 2, 3, 4, 5] })
+        ),
+        Err(limit_exceeded("max_size", "$"))
+    );
+    assert_eq!(
+        evaluator.partial_apply(&rule, &json!({ "a": "abc" })),
+        Ok(PartialApplyOutcome::Ambiguous)
+    );
+}
+
+#[test]
+fn size_of_data() {
+    let evaluator = evaluator(Limits {
+        max_size: Some(3),
+        ..Default::default()
+    });
+    let data = json!({ "name": "abcdef", "items": [1, 2, 3, 4] });
+
+    assert_eq!(
+        evaluator.apply(&json!({ "==": [{ "var": "name" }, "abcdef"] }), &data),
+        Ok(json!(true))
+    );
+    assert_eq!(
+        evaluator.apply(&json!({ "var": "items" }), &data),
+        Ok(json!([1, 2, 3, 4]))
+    );
+    assert_eq!(
+        evaluator.apply(
+            &json!({ "if": [true, { "or": [{ "var": "name" }, "x"] }] }),
+            &data
+        ),
+        Ok(json!("abcdef"))
+    );
+    assert_eq!(
+        evaluator.apply(&json!({ "in": [4, { "var": "items" }] }), &data),
+        Ok(json!(true))
+    );
+    assert_eq!(
+        evaluator.apply(&json!({ "if": [true, { "cat": ["ab", "cd"] }] }), &data),
+        Err(limit_exceeded("max_size", "$.if[1]"))
+    );
+}
+
+#[test]
+fn default_depth() {
+    let mut rule = json!(true);
+    for _ in 0..=DEFAULT_MAX_DEPTH {
+        rule = json!({ "!": rule });
+    }
+    let path = format!("${}", "['!']".repeat(DEFAULT_MAX_DEPTH));
+    assert_eq!(
+        Expression::from_json(&rule),
+        Err(limit_exceeded("max_depth", &path))
+    );
+    assert_eq!(
+        Evaluator::new().compile(&rule).unwrap_err(),
+        limit_exceeded("max_depth", &path)
+    );
+    assert_eq!(
+        validate(&rule, &ValidationConfig::default()),
+        Err(ValidationError::new(
+            &format!("Maximum nesting of {} exceeded", DEFAULT_MAX_DEPTH),
+            &path
+        ))
+    );
+
+    let unlimited = evaluator(Limits {
+        max_depth: None,
+        ..Default::default()
+    });
+    assert_eq!(unlimited.apply(&rule, &Value::Null), Ok(json!(false)));
+}
+
+#[test]
+fn steps_and_size_unlimited_by_default() {
+    let evaluator = Evaluator::new();
+    assert_eq!(evaluator.limits(), &Limits::default());
+
+    let items: Vec<usize> = (0..10_000).collect();
+    let rule = json!({ "map": [{ "var": "items" }, { "+": [{ "var": "" }, 1] }] });
+    assert!(evaluator.apply(&rule, &json!({ "items": items })).is_ok());
+}
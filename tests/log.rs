@@ -0,0 +1,122 @@
+use jsonlogic::log_sink::{CapturingSink, DisabledSink};
+use jsonlogic::Evaluator;
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+
+fn capturing_evaluator() -> (Evaluator, CapturingSink) {
+    let sink = CapturingSink::new();
+    let mut evaluator = Evaluator::new();
+    evaluator.set_log_sink(sink.clone());
+    (evaluator, sink)
+}
+
+#[test]
+fn capture() {
+    let (evaluator, sink) = capturing_evaluator();
+    let rule = json!({
+        "and": [
+            { "log": { "var": "a" } },
+            { "==": [{ "log": [{ "var": "b" }] }, 2] }
+        ]
+    });
+
+    assert_eq!(
+        evaluator.apply(&rule, &json!({ "a": 1, "b": 2 })),
+        Ok(json!(true))
+    );
+    assert_eq!(
+        sink.entries(),
+        vec![
+            (json!(1), String::from("$.and[0]")),
+            (json!(2), String::from("$.and[1]['=='][0]")),
+        ]
+    );
+
+    sink.clear();
+    assert!(sink.entries().is_empty());
+}
+
+#[test]
+fn paths() {
+    let (evaluator, sink) = capturing_evaluator();
+
+    evaluator
+        .apply(
+            &json!({ "or": [{ "log": [] }, { "log": [] }] }),
+            &Value::Null,
+        )
+        .unwrap();
+    evaluator
+        .apply(
+            &json!({ "let": ["x", { "log": 1 }, { "+": [{ "var": "x" }, { "log": 2 }] }] }),
+            &Value::Null,
+        )
+        .unwrap();
+    evaluator
+        .partial_apply_residual(
+            &json!({ "and": [{ "var": "a" }, { "log": [{ "var": "b" }] }] }),
+            &json!({ "b": 3 }),
+        )
+        .unwrap();
+
+    let paths: Vec<String> = sink.entries().into_iter().map(|(_, path)| path).collect();
+    assert_eq!(
+        paths,
+        vec![
+            "$.or[0]",
+            "$.or[1]",
+            "$.let[1]",
+            "$.let[2]['+'][1]",
+            "$.and[1]",
+        ]
+    );
+}
+
+#[test]
+fn compiled_rules_use_the_sink() {
+    let (evaluator, sink) = capturing_evaluator();
+    let rule = evaluator
+        .compile(&json!({ "map": [[1, 2], { "log": { "var": "" } }] }))
+        .unwrap();
+
+    assert_eq!(rule.evaluate(&Value::Null), Ok(json!([1, 2])));
+    let (_, _trace) = rule.evaluate_with_trace(&Value::Null).unwrap();
+    assert_eq!(
+        rule.partial_evaluate(&Value::Null),
+        Ok(jsonlogic::PartialApplyOutcome::Resolved(json!([1, 2])))
+    );
+
+    let values: Vec<Value> = sink.entries().into_iter().map(|(value, _)| value).collect();
+    assert_eq!(
+        values,
+        vec![json!(1), json!(2), json!(1), json!(2), json!(1), json!(2)]
+    );
+    assert_eq!(sink.entries()[0].1, "$.map[1]");
+}
+
+#[test]
+fn closure() {
+    let logged = Arc::new(Mutex::new(vec![]));
+    let mut evaluator = Evaluator::new();
+    {
+        let logged = Arc::clone(&logged);
+        evaluator.set_log_sink(move |value: &Value, _path: &str| {
+            logged.lock().unwrap().push(value.clone())
+        });
+    }
+
+    evaluator
+        .apply(&json!({ "log": "hello" }), &Value::Null)
+        .unwrap();
+    assert_eq!(*logged.lock().unwrap(), vec![json!("hello")]);
+}
+
+#[test]
+fn disabled() {
+    let mut evaluator = Evaluator::new();
+    evaluator.set_log_sink(DisabledSink);
+    assert_eq!(
+        evaluator.apply(&json!({ "log": [[1, 2]] }), &Value::Null),
+        Ok(json!([1, 2]))
+    );
+}
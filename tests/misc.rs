@@ -1,4 +1,5 @@
 use jsonlogic::apply;
+use jsonlogic::expression::Expression;
 use serde_json::{json, Value};
 
 #[test]
@@ -16,3 +17,39 @@ fn log() {
         Ok(json!("foo"))
     );
 }
+
+#[test]
+fn expression_round_trip() {
+    let rules = vec![
+        json!({ "var": "a" }),
+        json!({ "!": true }),
+        json!({ "missing": null }),
+        json!({ "if": [{ "<": [{ "var": "a" }, 1, 3] }, "in", { "cat": ["out", { "var": "b" }] }] }),
+        json!({ "map": [{ "var": "items" }, { "*": [{ "var": "" }, 2] }] }),
+        json!({ "in": ["b", ["a", "b"]] }),
+        json!({ "==": [null, 0] }),
+    ];
+    let data = json!({ "a": 2, "b": "side", "items": [1, 2] });
+
+    for rule in rules {
+        let expr = Expression::from_json(&rule).unwrap();
+        let json = expr.to_json();
+        assert_eq!(Expression::from_json(&json).unwrap(), expr);
+        // The canonical rule is stable and evaluates like the original one.
+        assert_eq!(Expression::from_json(&json).unwrap().to_json(), json);
+        assert_eq!(apply(&json, &data), apply(&rule, &data));
+    }
+
+    assert_eq!(
+        Expression::from_json(&json!({ "var": "a" }))
+            .unwrap()
+            .to_json(),
+        json!({ "var": ["a"] })
+    );
+    assert_eq!(
+        Expression::from_json(&json!({ "missing": null }))
+            .unwrap()
+            .to_json(),
+        json!({ "missing": [] })
+    );
+}
@@ -1,4 +1,4 @@
-use jsonlogic::apply;
+use jsonlogic::{apply, partial_apply, Error, Evaluator, PartialApplyOutcome};
 use serde_json::{json, Value};
 
 #[test]
@@ -130,3 +130,187 @@ fn modulo() {
         Ok(json!(0.0))
     );
 }
+
+// Results that are NaN or infinite cannot be represented in JSON and become null.
+#[test]
+fn non_finite_results() {
+    let cases = [
+        json!({"+": ["1e308", "1e308"]}),
+        json!({"+": [1e308, 1e308]}),
+        json!({"-": [-1e308, 1e308]}),
+        json!({"-": ["1e400"]}),
+        json!({"*": [1e200, 1e200]}),
+        json!({"*": ["1e400", 0]}),
+        json!({"/": [1, 0]}),
+        json!({"/": [0, 0]}),
+        json!({"/": [-1, 0]}),
+        json!({"%": [1, 0]}),
+        json!({"%": ["1e400", 2]}),
+        json!({"min": ["1e400", "2e400"]}),
+        json!({"max": [1, "1e400"]}),
+        json!({"max": ["NaN"]}),
+    ];
+
+    for rule in cases.iter() {
+        assert_eq!(apply(rule, &Value::Null), Ok(Value::Null), "{}", rule);
+        assert_eq!(
+            partial_apply(rule, &Value::Null),
+            Ok(PartialApplyOutcome::Resolved(Value::Null)),
+            "{}",
+            rule
+        );
+    }
+
+    assert_eq!(
+        apply(&json!({"min": [1, "-1e400"]}), &Value::Null),
+        Ok(Value::Null)
+    );
+    assert_eq!(
+        apply(&json!({"min": [1, "1e400"]}), &Value::Null),
+        Ok(json!(1.0))
+    );
+    assert_eq!(
+        apply(&json!({"<": [{"+": [1e308, 1e308]}, 1]}), &Value::Null),
+        Ok(json!(true))
+    );
+}
+
+fn integer_evaluator() -> Evaluator {
+    let mut evaluator = Evaluator::new();
+    evaluator.set_integer_arithmetic(true);
+    evaluator
+}
+
+// Integer mode keeps integers exact and falls back to floats for other operands.
+#[test]
+fn integer_arithmetic() {
+    let evaluator = integer_evaluator();
+    let cases = [
+        (json!({"+": [1, 2]}), json!(3)),
+        (
+            json!({"+": [9007199254740993u64, 2]}),
+            json!(9007199254740995u64),
+        ),
+        (json!({"+": ["10", " 20 "]}), json!(30)),
+        (json!({"+": [1, 0.5]}), json!(1.5)),
+        (json!({"+": [1, "2.5"]}), json!(3.5)),
+        (json!({"+": [1, null]}), Value::Null),
+        (json!({"-": [5, 7]}), json!(-2)),
+        (json!({"-": [5]}), json!(-5)),
+        (
+            json!({"-": [18446744073709551615u64, 1]}),
+            json!(18446744073709551614u64),
+        ),
+        (
+            json!({"*": [4611686018427387904u64, 2]}),
+            json!(9223372036854775808u64),
+        ),
+        (json!({"*": [2, 1.5]}), json!(3.0)),
+        (json!({"%": [-7, 3]}), json!(-1)),
+        (json!({"%": [7, 0]}), Value::Null),
+        (json!({"min": [3, "2", null]}), json!(0)),
+        (
+            json!({"max": [9007199254740993u64, 9007199254740992u64]}),
+            json!(9007199254740993u64),
+        ),
+        (json!({"max": [1, 2.5]}), json!(2.5)),
+        (json!({"/": [4, 2]}), json!(2.0)),
+    ];
+
+    for (rule, expected) in cases.iter() {
+        assert_eq!(
+            evaluator.apply(rule, &Value::Null),
+            Ok(expected.clone()),
+            "{}",
+            rule
+        );
+        assert_eq!(
+            evaluator.partial_apply(rule, &Value::Null),
+            Ok(PartialApplyOutcome::Resolved(expected.clone())),
+            "{}",
+            rule
+        );
+    }
+
+    assert_eq!(
+        evaluator.apply(
+            &json!({"+": [{"var": "amount"}, {"var": "fee"}]}),
+            &json!({"amount": 12345678901234567u64, "fee": 1})
+        ),
+        Ok(json!(12345678901234568u64))
+    );
+}
+
+#[test]
+fn integer_overflow() {
+    let evaluator = integer_evaluator();
+    let cases = [
+        (json!({"+": [18446744073709551615u64, 1]}), "+", "$"),
+        (
+            json!({"if": [true, {"-": [-9223372036854775808i64, 1]}]}),
+            "-",
+            "$.if[1]",
+        ),
+        (json!({"-": [18446744073709551615u64]}), "-", "$"),
+        (json!({"*": [{"var": "a"}, {"var": "a"}]}), "*", "$"),
+    ];
+
+    for (rule, operator, path) in cases.iter() {
+        assert_eq!(
+            evaluator.apply(rule, &json!({"a": 4294967296u64 * 16})),
+            Err(Error::IntegerOverflow {
+                operator: String::from(*operator),
+                path: String::from(*path),
+            }),
+            "{}",
+            rule
+        );
+    }
+
+    // The same operations are found while the residual of a rule is computed.
+    assert_eq!(
+        evaluator.partial_apply_residual(
+            &json!({"or": [{"var": "x"}, {"+": [{"var": "a"}, 18446744073709551615u64]}]}),
+            &json!({"a": 1})
+        ),
+        Err(Error::IntegerOverflow {
+            operator: String::from("+"),
+            path: String::from("$.or[1]"),
+        })
+    );
+
+    // Without integer mode, large results become floats.
+    assert_eq!(
+        apply(&json!({"+": [18446744073709551615u64, 1]}), &Value::Null),
+        Ok(json!(18446744073709551616.0))
+    );
+}
+
+// Integers are compared exactly, even where floats cannot represent them.
+#[test]
+fn compare_large_integers() {
+    let cases = [
+        (
+            json!({"<": [9007199254740992u64, 9007199254740993u64]}),
+            true,
+        ),
+        (
+            json!({">": [9007199254740993u64, 9007199254740992u64]}),
+            true,
+        ),
+        (
+            json!({"<=": [9007199254740993u64, 9007199254740992u64]}),
+            false,
+        ),
+        (json!({"<": [-1, 18446744073709551615u64]}), true),
+        (json!({"<": [18446744073709551615u64, -1]}), false),
+        (
+            json!({"<": [1, 9007199254740993u64, 9007199254740994u64]}),
+            true,
+        ),
+    ];
+
+    for (rule, expected) in cases.iter() {
+        assert_eq!(apply(rule, &Value::Null), Ok(json!(expected)), "{}", rule);
+    }
+}
@@ -1,3 +1,5 @@
+#![allow(clippy::redundant_closure)]
+
 use jsonlogic::{
     apply, partial_apply,
     PartialApplyOutcome::{Ambiguous, Resolved},
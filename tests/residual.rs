@@ -0,0 +1,259 @@
+use jsonlogic::data::Data;
+use jsonlogic::expression::{Expression, PartialResult};
+use jsonlogic::registry::{CustomOperator, OperatorRegistry};
+use jsonlogic::{
+    apply, partial_apply_residual, Evaluator,
+    PartialApplyOutcome::{Residual, Resolved},
+};
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Applies the residual of the rule for `known` to `rest` and checks that the result equals
+/// applying the whole rule to the merged data.
+fn assert_residual_consistent(logic: &Value, known: &Value, rest: &Value) {
+    let mut merged = known.clone();
+    for (key, value) in rest.as_object().unwrap() {
+        merged[key] = value.clone();
+    }
+
+    let expected = apply(logic, &merged).unwrap();
+    match partial_apply_residual(logic, known).unwrap() {
+        Residual(residual) => assert_eq!(apply(&residual, &merged), Ok(expected)),
+        Resolved(value) => assert_eq!(value, expected),
+        outcome => panic!("unexpected outcome {:?}", outcome),
+    }
+}
+
+#[test]
+fn and_or() {
+    let logic = json!({
+      "and": [
+        { "==": [{ "var": "status" }, "student"] },
+        { ">": [{ "var": "age" }, 18] }
+      ]
+    });
+    assert_eq!(
+        partial_apply_residual(&logic, &json!({ "status": "student" })),
+        Ok(Residual(json!({ ">": [{ "var": ["age"] }, 18] })))
+    );
+    assert_eq!(
+        partial_apply_residual(&logic, &json!({ "status": "employed" })),
+        Ok(Resolved(json!(false)))
+    );
+    assert_eq!(
+        partial_apply_residual(&logic, &json!({ "age": 20 })),
+        Ok(Residual(
+            json!({ "and": [{ "==": [{ "var": ["status"] }, "student"] }, true] })
+        ))
+    );
+
+    let logic = json!({
+      "or": [
+        { "==": [{ "var": "status" }, "employed"] },
+        { "==": [{ "var": "status" }, "student"] },
+        { "<": [{ "var": "age" }, 18] }
+      ]
+    });
+    assert_eq!(
+        partial_apply_residual(&logic, &json!({ "status": "retired" })),
+        Ok(Residual(json!({ "<": [{ "var": ["age"] }, 18] })))
+    );
+    assert_eq!(
+        partial_apply_residual(&logic, &json!({ "status": "student" })),
+        Ok(Resolved(json!(true)))
+    );
+}
+
+#[test]
+fn and_or_after_residual() {
+    assert_eq!(
+        partial_apply_residual(&json!({ "or": [{ "var": "x" }, true] }), &json!({})),
+        Ok(Residual(json!({ "or": [{ "var": ["x"] }, true] })))
+    );
+    assert_eq!(
+        partial_apply_residual(
+            &json!({ "and": [{ "var": "x" }, 0, { "var": "y" }] }),
+            &json!({ "y": 1 })
+        ),
+        Ok(Residual(json!({ "and": [{ "var": ["x"] }, 0] })))
+    );
+    assert_eq!(
+        partial_apply_residual(
+            &json!({ "or": [{ "var": "x" }, 0, { "var": "y" }] }),
+            &json!({ "y": "b" })
+        ),
+        Ok(Residual(json!({ "or": [{ "var": ["x"] }, "b"] })))
+    );
+
+    let logic = json!({ "or": [{ "var": "x" }, "fallback", { "var": "y" }] });
+    for x in &[json!("a"), json!(0), json!([])] {
+        assert_residual_consistent(&logic, &json!({ "y": 1 }), &json!({ "x": x }));
+    }
+}
+
+#[test]
+fn residuals_are_computed_once() {
+    static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+    /// Returns its argument and counts how often it is partially computed.
+    struct Counted;
+
+    impl CustomOperator for Counted {
+        fn compute(&self, args: &[Expression], data: &Data) -> Value {
+            args[0].compute(data)
+        }
+
+        fn partial_compute(&self, args: &[Expression], data: &Data) -> PartialResult {
+            CALLS.fetch_add(1, Ordering::SeqCst);
+            args[0].partial_compute(data)
+        }
+    }
+
+    let mut registry = OperatorRegistry::new();
+    registry.register("counted", Counted).unwrap();
+    let evaluator = Evaluator::with_registry(registry);
+    let logic = json!({ "==": [{ "+": [{ "counted": [{ "var": "x" }] }, 1] }, 3] });
+
+    assert_eq!(
+        evaluator.partial_apply_residual(&logic, &json!({})),
+        Ok(Residual(
+            json!({ "==": [{ "+": [{ "counted": [{ "var": ["x"] }] }, 1] }, 3] })
+        ))
+    );
+    assert_eq!(CALLS.load(Ordering::SeqCst), 1);
+
+    // Resolved arguments still decide the result without the residuals.
+    assert_eq!(
+        partial_apply_residual(&json!({ "<": [3, 2, { "var": "x" }] }), &json!({})),
+        Ok(Resolved(json!(false)))
+    );
+}
+
+#[test]
+fn if_else() {
+    let logic = json!({
+      "if": [
+        { "var": "a" }, "first",
+        { "==": [{ "var": "b" }, 2] }, "second",
+        { "var": "c" }, "third",
+        "fourth"
+      ]
+    });
+    assert_eq!(
+        partial_apply_residual(&logic, &json!({ "b": 2 })),
+        Ok(Residual(
+            json!({ "if": [{ "var": ["a"] }, "first", "second"] })
+        ))
+    );
+    assert_eq!(
+        partial_apply_residual(&logic, &json!({ "b": 3 })),
+        Ok(Residual(json!({
+          "if": [{ "var": ["a"] }, "first", { "var": ["c"] }, "third", "fourth"]
+        })))
+    );
+    assert_eq!(
+        partial_apply_residual(&logic, &json!({ "a": false, "b": 3, "c": 0 })),
+        Ok(Resolved(json!("fourth")))
+    );
+    assert_eq!(
+        partial_apply_residual(&logic, &json!({ "a": false })),
+        Ok(Residual(json!({
+          "if": [{ "==": [{ "var": ["b"] }, 2] }, "second", { "var": ["c"] }, "third", "fourth"]
+        })))
+    );
+}
+
+#[test]
+fn arguments_are_folded() {
+    assert_eq!(
+        partial_apply_residual(
+            &json!({ "+": [{ "var": "x" }, { "*": [{ "var": "y" }, 3] }] }),
+            &json!({ "y": 2 })
+        ),
+        Ok(Residual(json!({ "+": [{ "var": ["x"] }, 6.0] })))
+    );
+
+    // Resolves even though the middle argument is unknown, since 2 < 1 is already false.
+    assert_eq!(
+        partial_apply_residual(&json!({ "<": [2, { "var": "x" }, 1] }), &Value::Null),
+        Ok(Resolved(json!(false)))
+    );
+}
+
+#[test]
+fn scoped_arguments_are_kept() {
+    let logic = json!({
+      "map": [
+        { "var": "items" },
+        { "*": [{ "var": "price" }, { "var": "rate" }] }
+      ]
+    });
+    // `rate` is evaluated against the array elements, so it must not be taken from the data.
+    assert_eq!(
+        partial_apply_residual(&logic, &json!({ "rate": 2 })),
+        Ok(Residual(json!({
+          "map": [{ "var": ["items"] }, { "*": [{ "var": ["price"] }, { "var": ["rate"] }] }]
+        })))
+    );
+    assert_eq!(
+        partial_apply_residual(
+            &logic,
+            &json!({ "items": [{ "price": 1, "rate": 3 }, { "price": 2, "rate": 3 }] })
+        ),
+        Ok(Resolved(json!([3.0, 6.0])))
+    );
+}
+
+#[test]
+fn single_key_object_values_are_not_inlined() {
+    let logic = json!({ "==": [{ "var": "obj" }, { "var": "other" }] });
+    assert_eq!(
+        partial_apply_residual(&logic, &json!({ "obj": { "a": 1 } })),
+        Ok(Residual(
+            json!({ "==": [{ "var": ["obj"] }, { "var": ["other"] }] })
+        ))
+    );
+}
+
+#[test]
+fn residual_matches_apply() {
+    let logic = json!({
+      "and": [
+        { ">": [{ "var": "age" }, 18] },
+        { "<=": [{ "var": "age" }, 65] },
+        { "==": [{ "var": "test" }, "test"] },
+        {
+          "or": [
+            { "==": [{ "var": "status" }, "employed"] },
+            { "==": [{ "var": "status" }, "student"] }
+          ]
+        }
+      ]
+    });
+    let full = json!({ "age": 30, "test": "test", "status": "student" });
+    for key in ["age", "test", "status"].iter() {
+        let mut known = full.clone();
+        let value = known.as_object_mut().unwrap().remove(*key).unwrap();
+        let mut rest = serde_json::Map::new();
+        rest.insert(key.to_string(), value);
+        assert_residual_consistent(&logic, &known, &Value::Object(rest));
+    }
+
+    let logic = json!({
+      "if": [
+        { "==": [{ "var": "role" }, "admin"] },
+        { "and": [{ "==": [{ "var": "accessLevel" }, "full"] }, { "==": [{ "var": "active" }, true] }]},
+        { "==": [{ "var": "accessLevel" }, "limited"] }
+      ]
+    });
+    assert_residual_consistent(
+        &logic,
+        &json!({ "accessLevel": "limited" }),
+        &json!({ "role": "user" }),
+    );
+    assert_residual_consistent(
+        &logic,
+        &json!({ "accessLevel": "full" }),
+        &json!({ "role": "admin", "active": true }),
+    );
+}
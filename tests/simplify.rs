@@ -0,0 +1,71 @@
+use jsonlogic::{apply, simplify, Evaluator};
+use serde_json::{json, Value};
+
+/// Checks that the simplified rule evaluates to the same value as the rule for all data.
+fn assert_equivalent(logic: &Value, data: &[Value]) {
+    let simplified = simplify(logic).unwrap();
+    for data in data {
+        assert_eq!(
+            apply(&simplified, data),
+            apply(logic, data),
+            "{} simplified to {} with {}",
+            logic,
+            simplified,
+            data
+        );
+    }
+}
+
+#[test]
+fn preserves_semantics() {
+    let data = [
+        Value::Null,
+        json!({ "a": 0, "b": "", "c": [] }),
+        json!({ "a": 2, "b": "x", "c": [1, 2] }),
+        json!({ "a": "3", "b": [0], "c": { "d": 1 } }),
+        json!({ "a": -0.0, "b": null, "c": "1,2" }),
+    ];
+
+    for logic in &[
+        json!({ "and": [true, { "var": "a" }, 1, { "and": [{ "var": "b" }, "x"] }] }),
+        json!({ "or": [{ "or": [false, { "var": "a" }] }, 0, { "var": "b" }, ""] }),
+        json!({ "and": [{ "var": "a" }, [], { "var": "b" }] }),
+        json!({ "if": [0, 1, { "var": "a" }, { "var": "b" }, "", 3, { "var": "c" }] }),
+        json!({ "if": [{ "var": "a" }, 1, [1], { "var": "b" }, 3] }),
+        json!({ "if": [{ "!": [true] }, { "var": "a" }] }),
+        json!({ "+": [{ "+": [0, { "var": "a" }] }, 0, { "+": [{ "var": "a" }, 0.1] }] }),
+        json!({ "+": [{ "var": "a" }, 0] }),
+        json!({ "*": [{ "*": [{ "var": "a" }, 1] }, 1] }),
+        json!({ "*": [1, { "var": "b" }, 1] }),
+        json!({ "cat": ["", { "cat": [{ "var": "a" }, ""] }, { "var": "c" }] }),
+        json!({ "merge": [[], { "merge": [{ "var": "c" }, []] }, { "var": "a" }] }),
+        json!({ "map": [{ "var": "c" }, { "+": [{ "var": "" }, { "*": [2, 3] }] }] }),
+        json!({ "let": ["x", { "+": [1, 2] }, { "cat": [{ "var": "x" }, { "var": "b" }] }] }),
+        json!({ "==": [{ "var": "a" }, { "if": [true, { "-": [5, 3] }, 0] }] }),
+    ] {
+        assert_equivalent(logic, &data);
+    }
+}
+
+#[test]
+fn evaluator_settings() {
+    let mut evaluator = Evaluator::new();
+    evaluator.set_integer_arithmetic(true);
+
+    let rule = evaluator
+        .compile(&json!({ "+": [{ "var": "a" }, { "*": [2, 3] }] }))
+        .unwrap()
+        .simplify()
+        .unwrap();
+    assert_eq!(
+        rule.expression().to_json(),
+        json!({ "+": [{ "var": ["a"] }, 6] })
+    );
+    assert_eq!(rule.evaluate(&json!({ "a": 1 })), Ok(json!(7)));
+
+    // The overflow is kept, so it still aborts the evaluation.
+    let logic = json!({ "*": [{ "var": "a" }, { "+": [u64::MAX, 1] }] });
+    let rule = evaluator.compile(&logic).unwrap().simplify().unwrap();
+    assert_eq!(rule, evaluator.compile(&logic).unwrap());
+    assert!(rule.evaluate(&json!({ "a": 1 })).is_err());
+}
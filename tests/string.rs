@@ -1,4 +1,4 @@
-use jsonlogic::apply;
+use jsonlogic::{apply, partial_apply, Error, Evaluator, PartialApplyOutcome};
 use serde_json::{json, Value};
 
 // in
@@ -77,3 +77,194 @@ fn regex_match() {
         Ok(json!(false))
     );
 }
+
+// match with patterns that are computed when the rule is applied
+#[test]
+fn regex_match_dynamic_pattern() {
+    let rule = json!({"match": [{"var": "text"}, {"var": "pattern"}, {"var": "flags"}]});
+    let cases = [
+        (
+            json!({"text": "ABC", "pattern": "^abc$", "flags": "i"}),
+            true,
+        ),
+        (json!({"text": "ABC", "pattern": "^abc$"}), false),
+        (json!({"text": "a1", "pattern": "\\d"}), true),
+        (
+            json!({"text": "ABC", "pattern": "^abc$", "flags": "i"}),
+            true,
+        ),
+        (json!({"text": "abc", "pattern": "("}), false),
+    ];
+
+    for size in [0, 1, 256].iter() {
+        let mut evaluator = jsonlogic::Evaluator::new();
+        evaluator.set_regex_cache_size(*size);
+        assert_eq!(evaluator.regex_cache_size(), *size);
+
+        let compiled = evaluator.compile(&rule).unwrap();
+        for (data, expected) in cases.iter() {
+            assert_eq!(compiled.evaluate(data), Ok(json!(expected)), "{}", data);
+        }
+    }
+}
+
+// match with invalid literal patterns or flags
+#[test]
+fn regex_match_invalid_literal() {
+    assert_eq!(
+        apply(
+            &json!({"or": [{"var": "a"}, {"match": [{"var": "b"}, "[a-"]}]}),
+            &Value::Null
+        )
+        .unwrap_err()
+        .path(),
+        Some("$.or[1]")
+    );
+
+    for flag in ["y", "u"].iter() {
+        assert_eq!(
+            apply(&json!({"match": ["abc", "b", flag]}), &Value::Null),
+            Err(Error::InvalidRegex {
+                pattern: String::from("b"),
+                message: format!("Unsupported regex flag '{}'", flag),
+                path: String::from("$"),
+            })
+        );
+    }
+}
+
+// match with invalid computed patterns in strict regex mode
+#[test]
+fn regex_match_strict() {
+    let rule = json!({"and": [true, {"match": ["abc", {"var": "pattern"}, {"var": "flags"}]}]});
+    let mut evaluator = Evaluator::new();
+    assert_eq!(
+        evaluator.apply(&rule, &json!({"pattern": "("})),
+        Ok(json!(false))
+    );
+
+    evaluator.set_strict_regex(true);
+    let err = evaluator
+        .apply(&rule, &json!({"pattern": "("}))
+        .unwrap_err();
+    assert_eq!(err.path(), Some("$.and[1]"));
+    assert!(matches!(err, Error::InvalidRegex { ref pattern, .. } if pattern == "("));
+    assert!(evaluator
+        .apply(&rule, &json!({"pattern": "b", "flags": "u"}))
+        .is_err());
+    assert_eq!(
+        evaluator.apply(&rule, &json!({"pattern": "B", "flags": "i"})),
+        Ok(json!(true))
+    );
+}
+
+// match_groups
+#[test]
+fn match_groups() {
+    assert_eq!(
+        apply(
+            &json!({"match_groups": [{"var": "card"}, "^(\\d{6})\\d*$"]}),
+            &json!({"card": "4111111111111111"})
+        ),
+        Ok(json!(["4111111111111111", "411111"]))
+    );
+    assert_eq!(
+        apply(
+            &json!({"match_groups": [{"var": "ua"}, "build (?P<build>\\d+)", "i"]}),
+            &json!({"ua": "App/1.2 (Build 345)"})
+        ),
+        Ok(json!({"build": "345"}))
+    );
+    assert_eq!(
+        apply(&json!({"match_groups": ["abc", "\\d"]}), &Value::Null),
+        Ok(Value::Null)
+    );
+}
+
+// replace
+#[test]
+fn replace() {
+    assert_eq!(
+        apply(
+            &json!({"replace": [{"var": "phone"}, "(\\d{3})(\\d{3})", "$1-$2-"]}),
+            &json!({"phone": "1234567890"})
+        ),
+        Ok(json!("123-456-7890"))
+    );
+    assert_eq!(
+        apply(
+            &json!({"replace": ["a.b.c", "\\.", "/", "g"]}),
+            &Value::Null
+        ),
+        Ok(json!("a/b/c"))
+    );
+    assert_eq!(
+        apply(&json!({"replace": ["a.b.c", "\\.", "/"]}), &Value::Null),
+        Ok(json!("a/b.c"))
+    );
+    // References to groups that do not exist are kept literally.
+    assert_eq!(
+        apply(
+            &json!({"replace": ["2020-01", "(?<year>\\d+)-(\\d+)", "$2/$<year> $3 $<day>"]}),
+            &Value::Null
+        ),
+        Ok(json!("01/2020 $3 $<day>"))
+    );
+}
+
+// split_regex
+#[test]
+fn split_regex() {
+    assert_eq!(
+        apply(
+            &json!({"split_regex": [{"var": "tags"}, "\\s*[,;]\\s*"]}),
+            &json!({"tags": "a, b;c"})
+        ),
+        Ok(json!(["a", "b", "c"]))
+    );
+}
+
+// the regex operators report invalid patterns and flags like match
+#[test]
+fn regex_operators_invalid() {
+    assert_eq!(
+        apply(&json!({"replace": ["abc", "b", "x", "gy"]}), &Value::Null)
+            .unwrap_err()
+            .path(),
+        Some("$")
+    );
+    assert!(apply(&json!({"split_regex": ["abc", "("]}), &Value::Null).is_err());
+
+    let mut evaluator = Evaluator::new();
+    evaluator.set_strict_regex(true);
+    assert!(evaluator
+        .apply(
+            &json!({"match_groups": ["abc", {"var": "pattern"}]}),
+            &json!({"pattern": "("})
+        )
+        .is_err());
+}
+
+// the regex operators can be computed with partial data
+#[test]
+fn regex_operators_partial() {
+    let rule = json!({"replace": [{"var": "text"}, {"var": "pattern"}, "-", "g"]});
+    assert_eq!(
+        partial_apply(&rule, &json!({"text": "a b"})),
+        Ok(PartialApplyOutcome::Ambiguous)
+    );
+    assert_eq!(
+        partial_apply(&rule, &json!({"text": "a b", "pattern": " "})),
+        Ok(PartialApplyOutcome::Resolved(json!("a-b")))
+    );
+
+    let rule = json!({"match_groups": [{"var": "text"}, "(a)"]});
+    assert_eq!(
+        partial_apply(&rule, &json!({})),
+        Ok(PartialApplyOutcome::Ambiguous)
+    );
+    assert_eq!(
+        partial_apply(&json!({"split_regex": ["a1b", "\\d"]}), &json!({})),
+        Ok(PartialApplyOutcome::Resolved(json!(["a", "b"])))
+    );
+}
@@ -0,0 +1,144 @@
+use jsonlogic::trace::TraceNode;
+use jsonlogic::{apply, apply_with_trace, CompiledRule};
+use serde_json::{json, Value};
+
+fn node(operator: &str, path: &str, arguments: Vec<Option<Value>>, result: Value) -> TraceNode {
+    TraceNode {
+        operator: String::from(operator),
+        path: String::from(path),
+        arguments,
+        result,
+        short_circuited: vec![],
+        children: vec![],
+    }
+}
+
+#[test]
+fn records_operations() {
+    let rule = json!({ "<": [{ "+": [{ "var": "a" }, 1] }, 10] });
+    let (result, trace) = apply_with_trace(&rule, &json!({ "a": 5 })).unwrap();
+
+    assert_eq!(result, json!(true));
+    assert_eq!(
+        trace.root,
+        Some(TraceNode {
+            children: vec![TraceNode {
+                children: vec![node(
+                    "var",
+                    "$['<'][0]['+'][0]",
+                    vec![Some(json!("a"))],
+                    json!(5)
+                )],
+                ..node(
+                    "+",
+                    "$['<'][0]",
+                    vec![Some(json!(5)), Some(json!(1))],
+                    json!(6.0)
+                )
+            }],
+            ..node(
+                "<",
+                "$",
+                vec![Some(json!(6.0)), Some(json!(10))],
+                json!(true)
+            )
+        })
+    );
+}
+
+#[test]
+fn short_circuit() {
+    let rule = json!({
+        "if": [
+            { "==": [{ "var": "method" }, "card"] }, "stripe",
+            { "==": [{ "var": "method" }, "upi"] }, "razorpay",
+            "paypal"
+        ]
+    });
+    let (result, trace) = apply_with_trace(&rule, &json!({ "method": "upi" })).unwrap();
+    let root = trace.root.unwrap();
+
+    assert_eq!(result, json!("razorpay"));
+    assert_eq!(root.short_circuited, vec![1, 4]);
+    assert_eq!(root.arguments[0], Some(json!(false)));
+    assert_eq!(root.arguments[3], Some(json!("razorpay")));
+    assert_eq!(
+        root.children
+            .iter()
+            .map(|c| c.path.as_str())
+            .collect::<Vec<_>>(),
+        vec!["$.if[0]", "$.if[2]"]
+    );
+
+    let rule = json!({ "or": [{ "var": "a" }, { "var": "b" }, { "var": "c" }] });
+    let (_, trace) = apply_with_trace(&rule, &json!({ "b": true })).unwrap();
+    assert_eq!(trace.root.unwrap().short_circuited, vec![2]);
+}
+
+#[test]
+fn array_operations() {
+    let rule = json!({ "map": [{ "var": "items" }, { "*": [{ "var": "" }, 2] }] });
+    let (result, trace) = apply_with_trace(&rule, &json!({ "items": [1, 2] })).unwrap();
+    let root = trace.root.unwrap();
+
+    assert_eq!(result, json!([2.0, 4.0]));
+    // The logic is recorded once for each element.
+    let paths: Vec<(&str, &Value)> = root
+        .children
+        .iter()
+        .map(|c| (c.path.as_str(), &c.result))
+        .collect();
+    assert_eq!(
+        paths,
+        vec![
+            ("$.map[0]", &json!([1, 2])),
+            ("$.map[1]", &json!(2.0)),
+            ("$.map[1]", &json!(4.0)),
+        ]
+    );
+}
+
+#[test]
+fn constant_rule() {
+    let (result, trace) = apply_with_trace(&json!([1, 2]), &Value::Null).unwrap();
+    assert_eq!(result, json!([1, 2]));
+    assert_eq!(trace.root, None);
+}
+
+#[test]
+fn matches_apply() {
+    let rule = json!({
+        "reduce": [
+            { "filter": [{ "var": "n" }, { ">": [{ "var": "" }, 1] }] },
+            { "+": [{ "var": "current" }, { "var": "accumulator" }] },
+            0
+        ]
+    });
+    let data = json!({ "n": [1, 2, 3] });
+    let compiled = CompiledRule::new(&rule).unwrap();
+
+    assert_eq!(
+        compiled.evaluate_with_trace(&data).unwrap().0,
+        apply(&rule, &data).unwrap()
+    );
+}
+
+#[test]
+fn serialize() {
+    let rule = json!({ "and": [false, { "var": "a" }] });
+    let (_, trace) = apply_with_trace(&rule, &Value::Null).unwrap();
+
+    assert_eq!(
+        serde_json::to_value(&trace).unwrap(),
+        json!({
+            "root": {
+                "operator": "and",
+                "path": "$",
+                "arguments": [false, null],
+                "result": false,
+                "short_circuited": [1],
+                "children": []
+            }
+        })
+    );
+}
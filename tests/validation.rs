@@ -1,7 +1,15 @@
 #[cfg(test)]
 mod tests {
-    use jsonlogic::validation::{validate, RequireAndWrapper, ValidationConfig};
-    use serde_json::json;
+    use jsonlogic::validation::{
+        validate, validate_all, validate_data, RequireAndWrapper, Severity, ValidationConfig,
+        ValidationError,
+    };
+    use serde_json::{json, Value};
+    use std::collections::HashSet;
+
+    fn set(items: &[&str]) -> Option<HashSet<String>> {
+        Some(items.iter().map(|item| item.to_string()).collect())
+    }
 
     #[test]
     fn test_and_wrapper_validation() {
@@ -33,7 +41,6 @@ mod tests {
             ..Default::default()
         };
 
-
         // Invalid unwrapped logic should fail
         assert!(validate(&invalid_logic, &config).is_err());
 
@@ -43,4 +50,384 @@ mod tests {
         // And-wrapped logic should still pass
         assert!(validate(&valid_logic, &strict_config).is_ok());
     }
+
+    #[test]
+    fn test_allowed_operators() {
+        let config = ValidationConfig {
+            allowed_operators: set(&["and", "==", "var"]),
+            ..Default::default()
+        };
+
+        let valid_logic = json!({
+            "and": [
+                {"==": [{"var": "age"}, 18]},
+                {"==": [{"var": "name"}, "Joe"]}
+            ]
+        });
+        assert!(validate(&valid_logic, &config).is_ok());
+
+        let invalid_logic = json!({
+            "and": [
+                {"==": [{"var": "age"}, 18]},
+                {"!": {"in": ["Jo", {"var": "name"}]}}
+            ]
+        });
+        assert_eq!(
+            validate(&invalid_logic, &config),
+            Err(ValidationError::new(
+                "Operator '!' is not allowed",
+                "$.and[1]"
+            ))
+        );
+
+        let invalid_logic = json!({
+            "and": [
+                {"==": [{"var": "age"}, 18]},
+                {"==": [{"in": ["Jo", {"var": "name"}]}, true]}
+            ]
+        });
+        assert_eq!(
+            validate(&invalid_logic, &config),
+            Err(ValidationError::new(
+                "Operator 'in' is not allowed",
+                "$.and[1]['=='][0]"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_max_depth() {
+        let config = ValidationConfig {
+            max_depth: Some(3),
+            ..Default::default()
+        };
+
+        assert!(validate(&json!(5), &config).is_ok());
+        assert!(validate(&json!({"and": [{"==": [{"var": "a"}, 1]}]}), &config).is_ok());
+        assert_eq!(
+            validate(
+                &json!({"and": [true, {"or": [{"!": {"var": "a"}}]}]}),
+                &config
+            ),
+            Err(ValidationError::new(
+                "Maximum depth of 3 exceeded",
+                "$.and[1].or[0]['!']"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_required_variables() {
+        let config = ValidationConfig {
+            required_variables: set(&["age", "name"]),
+            ..Default::default()
+        };
+
+        assert!(validate(
+            &json!({"and": [{">": [{"var": "age"}, 18]}, {"var": ["name", "Joe"]}]}),
+            &config
+        )
+        .is_ok());
+        assert_eq!(
+            validate(&json!({">": [{"var": "age"}, 18]}), &config),
+            Err(ValidationError::new(
+                "Required variable 'name' is not used",
+                "$"
+            ))
+        );
+        // Variables inside array operations refer to the elements, not the data.
+        assert!(validate(&json!({"some": [{"var": "age"}, {"var": "name"}]}), &config).is_err());
+    }
+
+    #[test]
+    fn test_allowed_variables() {
+        let config = ValidationConfig {
+            allowed_variables: set(&["user", "items"]),
+            ..Default::default()
+        };
+
+        assert!(validate(
+            &json!({"and": [
+                {"==": [{"var": "user.name"}, "Joe"]},
+                {"all": [{"var": "items"}, {">": [{"var": "price"}, 0]}]}
+            ]}),
+            &config
+        )
+        .is_ok());
+        assert_eq!(
+            validate(&json!({"==": [{"var": "username"}, "Joe"]}), &config),
+            Err(ValidationError::new(
+                "Variable 'username' is not allowed",
+                "$['=='][0]"
+            ))
+        );
+        assert_eq!(
+            validate(&json!({"==": [{"var": "user.name"}, {"var": ""}]}), &config),
+            Err(ValidationError::new(
+                "Variable '' is not allowed",
+                "$['=='][1]"
+            ))
+        );
+        assert_eq!(
+            validate(&json!({"var": {"cat": ["us", "er"]}}), &config),
+            Err(ValidationError::new(
+                "Variable names must be static if variables are restricted",
+                "$"
+            ))
+        );
+
+        // The keys of `missing` and `missing_some` are checked like `var` paths.
+        assert!(validate(
+            &json!({"and": [{"missing": ["user.name", "items"]}, {"missing_some": [1, ["user"]]}]}),
+            &config
+        )
+        .is_ok());
+        assert_eq!(
+            validate(&json!({"missing": [["user", "secret"]]}), &config),
+            Err(ValidationError::new(
+                "Variable 'secret' is not allowed",
+                "$"
+            ))
+        );
+        assert_eq!(
+            validate(&json!({"missing_some": [1, ["user", "secret"]]}), &config),
+            Err(ValidationError::new(
+                "Variable 'secret' is not allowed",
+                "$"
+            ))
+        );
+        assert_eq!(
+            validate(&json!({"missing": {"merge": ["user", "secret"]}}), &config),
+            Err(ValidationError::new(
+                "Variable names must be static if variables are restricted",
+                "$"
+            ))
+        );
+        // Paths that refer to the data from inside array operations are checked as well.
+        assert_eq!(
+            validate(
+                &json!({"map": [{"var": "items"}, {"var": "$root.secret"}]}),
+                &config
+            ),
+            Err(ValidationError::new(
+                "Variable '$root.secret' is not allowed",
+                "$.map[1]"
+            ))
+        );
+        assert_eq!(
+            validate(
+                &json!({"map": [{"var": "items"}, {"some": [{"var": "tags"}, {"var": "../../secret"}]}]}),
+                &config
+            ),
+            Err(ValidationError::new(
+                "Variable '../../secret' is not allowed",
+                "$.map[1].some[1]"
+            ))
+        );
+        assert!(validate(
+            &json!({"and": [
+                {"var": "$root.items"},
+                {"map": [{"var": "items"}, {"==": [{"var": "../user.id"}, {"var": "owner"}]}]}
+            ]}),
+            &config
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn let_bindings() {
+        let config = ValidationConfig {
+            allowed_variables: set(&["price"]),
+            required_variables: set(&["price", "total"]),
+            ..Default::default()
+        };
+
+        // `total` is bound by `let`, so it is neither checked nor counted as used.
+        assert_eq!(
+            validate_all(
+                &json!({"let": [
+                    "total", {"*": [{"var": "price"}, 2]},
+                    {">": [{"var": "total.amount"}, {"var": "tax"}]}
+                ]}),
+                &config
+            ),
+            vec![
+                ValidationError::new("Variable 'tax' is not allowed", "$.let[2]['>'][1]"),
+                ValidationError::new("Required variable 'total' is not used", "$"),
+            ]
+        );
+        // The name is not bound in its own value.
+        assert_eq!(
+            validate(&json!({"let": ["total", {"var": "total"}, 1]}), &config),
+            Err(ValidationError::new(
+                "Variable 'total' is not allowed",
+                "$.let[1]"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_data_schema_validator() {
+        let config = ValidationConfig {
+            data_schema_validator: Some(Box::new(|data: &Value| {
+                if data["age"].is_number() {
+                    Ok(())
+                } else {
+                    Err(ValidationError::new("age must be a number", "$.age"))
+                }
+            })),
+            ..Default::default()
+        };
+
+        assert!(validate_data(&json!({"age": 18}), &config).is_ok());
+        assert_eq!(
+            validate_data(&json!({"age": "18"}), &config),
+            Err(ValidationError::new("age must be a number", "$.age"))
+        );
+        assert!(validate_data(&json!({}), &ValidationConfig::default()).is_ok());
+    }
+
+    #[test]
+    fn test_validate_all() {
+        let config = ValidationConfig {
+            require_and_wrapper: Some(RequireAndWrapper { allow_empty: false }),
+            allowed_operators: set(&["and", "==", "var"]),
+            allowed_variables: set(&["age"]),
+            ..Default::default()
+        };
+
+        let logic = json!({
+            "or": [
+                {"==": [{"var": "age"}, 18]},
+                {"!=": [{"var": "name"}, "Joe"]},
+                {"==": [1, {"var": "x", "==": 1}]}
+            ]
+        });
+        let errors = validate_all(&logic, &config);
+        assert_eq!(
+            errors,
+            vec![
+                ValidationError::new("JSON Logic must be wrapped in an 'and' block", "$"),
+                ValidationError::new("Operator 'or' is not allowed", "$"),
+                ValidationError::new("Operator '!=' is not allowed", "$.or[1]"),
+                ValidationError::new("Variable 'name' is not allowed", "$.or[1]['!='][0]"),
+                ValidationError::warning(
+                    "Object with more than one key is a literal value, operator '==' is not applied",
+                    "$.or[2]['=='][1]"
+                ),
+            ]
+        );
+        assert_eq!(validate(&logic, &config), Err(errors[0].clone()));
+
+        // Warnings do not reject the rule.
+        let logic = json!({"and": [{"var": "age", "==": 1}]});
+        let errors = validate_all(&logic, &config);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].severity, Severity::Warning);
+        assert!(validate(&logic, &config).is_ok());
+    }
+
+    #[test]
+    fn test_check_signatures() {
+        let config = ValidationConfig {
+            check_signatures: true,
+            ..Default::default()
+        };
+
+        assert!(validate(
+            &json!({"and": [
+                {"<": [1, {"var": "a"}, 10]},
+                {"match": [{"var": "email"}, "^.+@example\\.com$", "i"]},
+                {"reduce": [{"var": "items"}, {"+": [{"var": "current"}, {"var": "accumulator"}]}, 0]},
+                {"substr": [{"var": "name"}, 0, {"var": "length"}]},
+                {"!": {"var": "disabled"}}
+            ]}),
+            &config
+        )
+        .is_ok());
+
+        assert_eq!(
+            validate(&json!({"and": [true, {"<": [{"var": "a"}]}]}), &config),
+            Err(ValidationError::new(
+                "Operator '<' expects 2 to 3 arguments, got 1",
+                "$.and[1]"
+            ))
+        );
+        assert_eq!(
+            validate(&json!({"if": {"var": "a"}}), &config),
+            Err(ValidationError::new(
+                "Operator 'if' expects at least 2 arguments, got 1",
+                "$"
+            ))
+        );
+        assert_eq!(
+            validate(&json!({"match": ["abc"]}), &config),
+            Err(ValidationError::new(
+                "Operator 'match' expects 2 to 3 arguments, got 1",
+                "$"
+            ))
+        );
+        assert_eq!(
+            validate(&json!({"jp_ver_lt": ["1.0", "2.0", "3.0", "4.0"]}), &config),
+            Err(ValidationError::new(
+                "Operator 'jp_ver_lt' expects 2 to 3 arguments, got 4",
+                "$"
+            ))
+        );
+        assert_eq!(
+            validate(&json!({"reduce": [[1, 2], 0, 0]}), &config),
+            Err(ValidationError::new(
+                "Operator 'reduce' expects an operation as this argument",
+                "$.reduce[1]"
+            ))
+        );
+        assert_eq!(
+            validate_all(&json!({"substr": ["abc", "1", true]}), &config),
+            vec![
+                ValidationError::new(
+                    "Operator 'substr' expects a number as this argument",
+                    "$.substr[1]"
+                ),
+                ValidationError::new(
+                    "Operator 'substr' expects a number as this argument",
+                    "$.substr[2]"
+                ),
+            ]
+        );
+
+        // Not checked unless enabled.
+        assert!(validate(&json!({"<": [1]}), &ValidationConfig::default()).is_ok());
+    }
+
+    #[test]
+    fn regex() {
+        let config = ValidationConfig::default();
+        assert_eq!(
+            validate(
+                &json!({"and": [true, {"match": [{"var": "a"}, "a(b"]}]}),
+                &config
+            )
+            .unwrap_err()
+            .path,
+            "$.and[1]"
+        );
+        assert_eq!(
+            validate(&json!({"match": [{"var": "a"}, "a", "y"]}), &config),
+            Err(ValidationError::new(
+                "Invalid regex: Unsupported regex flag 'y'",
+                "$"
+            ))
+        );
+        assert!(validate(&json!({"match": [{"var": "a"}, "^a$", "gi"]}), &config).is_ok());
+        assert!(validate(
+            &json!({"match": [{"var": "a"}, {"var": "p"}, "i"]}),
+            &config
+        )
+        .is_ok());
+        assert!(validate(
+            &json!({"match": [{"var": "a"}, "a", {"var": "f"}]}),
+            &config
+        )
+        .is_ok());
+    }
 }
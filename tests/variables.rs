@@ -0,0 +1,189 @@
+use jsonlogic::variables::{
+    ReferenceKind::{Missing, MissingSome, Var},
+    VariablePath::{self, Dynamic, Static},
+    VariableReference,
+};
+use jsonlogic::{variables, CompiledRule};
+use serde_json::{json, Value};
+
+fn reference(
+    path: &str,
+    kind: jsonlogic::variables::ReferenceKind,
+    location: &str,
+) -> VariableReference {
+    VariableReference {
+        path: Static(String::from(path)),
+        default: None,
+        kind,
+        scoped: false,
+        location: String::from(location),
+    }
+}
+
+#[test]
+fn var() {
+    assert_eq!(
+        variables(&json!({ "+": [{ "var": 1 }, { "var": ["b", 0] }, { "var": "" }] })).unwrap(),
+        vec![
+            reference("1", Var, "$['+'][0]"),
+            VariableReference {
+                default: Some(json!(0)),
+                ..reference("b", Var, "$['+'][1]")
+            },
+            reference("", Var, "$['+'][2]"),
+        ]
+    );
+    assert!(variables(&json!(5)).unwrap().is_empty());
+}
+
+#[test]
+fn dynamic() {
+    let refs = variables(&json!({
+        "var": [{ "cat": ["user.", { "var": "field" }] }, { "var": "fallback" }]
+    }))
+    .unwrap();
+
+    assert_eq!(
+        refs,
+        vec![
+            VariableReference {
+                path: Dynamic(json!({ "cat": ["user.", { "var": ["field"] }] })),
+                default: Some(json!({ "var": ["fallback"] })),
+                kind: Var,
+                scoped: false,
+                location: String::from("$"),
+            },
+            reference("field", Var, "$.var[0].cat[1]"),
+            reference("fallback", Var, "$.var[1]"),
+        ]
+    );
+    assert_eq!(refs[0].static_path(), None);
+}
+
+#[test]
+fn invalid_paths() {
+    let refs =
+        variables(&json!({ "and": [{ "var": true }, { "missing": [[false, ["a"]]] }] })).unwrap();
+
+    let paths: Vec<&VariablePath> = refs.iter().map(|r| &r.path).collect();
+    assert_eq!(
+        paths,
+        vec![
+            &Dynamic(json!(true)),
+            &Dynamic(json!(false)),
+            &Dynamic(json!(["a"]))
+        ]
+    );
+}
+
+#[test]
+fn missing() {
+    assert_eq!(
+        variables(&json!({
+            "if": [
+                { "missing": ["a", "b"] },
+                "missing a or b",
+                { "missing_some": [1, ["c", "d"]] },
+                "missing c and d",
+                "ok"
+            ]
+        }))
+        .unwrap(),
+        vec![
+            reference("a", Missing, "$.if[0]"),
+            reference("b", Missing, "$.if[0]"),
+            reference("c", MissingSome, "$.if[2]"),
+            reference("d", MissingSome, "$.if[2]"),
+        ]
+    );
+
+    let refs = variables(&json!({ "missing": { "merge": ["a", { "var": "keys" }] } })).unwrap();
+    assert_eq!(
+        refs[0].path,
+        Dynamic(json!({ "merge": ["a", { "var": ["keys"] }] }))
+    );
+    assert_eq!(refs[1], reference("keys", Var, "$.missing[0].merge[1]"));
+}
+
+#[test]
+fn scoped() {
+    let refs = variables(&json!({
+        "reduce": [
+            { "filter": [{ "var": "items" }, { ">": [{ "var": "price" }, 0] }] },
+            { "+": [{ "var": "current.price" }, { "var": "accumulator" }] },
+            { "var": "start" }
+        ]
+    }))
+    .unwrap();
+
+    let summary: Vec<(Option<&str>, bool)> =
+        refs.iter().map(|r| (r.static_path(), r.scoped)).collect();
+    assert_eq!(
+        summary,
+        vec![
+            (Some("items"), false),
+            (Some("price"), true),
+            (Some("current.price"), true),
+            (Some("accumulator"), true),
+            (Some("start"), false),
+        ]
+    );
+}
+
+#[test]
+fn outer_scopes() {
+    let refs = variables(&json!({
+        "all": [
+            { "var": "orders" },
+            { "some": [
+                { "var": "items" },
+                { "and": [
+                    { "==": [{ "var": "currency" }, { "var": "$root.currency" }] },
+                    { "==": [{ "var": "../id" }, { "var": "../../customer" }] },
+                    { "missing": ["../../../beyond", "$root"] }
+                ] }
+            ] }
+        ]
+    }))
+    .unwrap();
+
+    let summary: Vec<(Option<&str>, bool)> =
+        refs.iter().map(|r| (r.static_path(), r.scoped)).collect();
+    assert_eq!(
+        summary,
+        vec![
+            (Some("orders"), false),
+            (Some("items"), true),
+            (Some("currency"), true),
+            (Some("currency"), false),
+            (Some("id"), true),
+            (Some("customer"), false),
+            (Some("../../../beyond"), true),
+            (Some(""), false),
+        ]
+    );
+}
+
+#[test]
+fn compiled_rule() {
+    let rule = CompiledRule::new(&json!({ "==": [{ "var": "a" }, null] })).unwrap();
+    assert_eq!(rule.variables(), vec![reference("a", Var, "$['=='][0]")]);
+    assert!(variables(&json!({ "unknown": [] })).is_err());
+    assert!(variables(&Value::Null).unwrap().is_empty());
+}
+
+#[test]
+fn let_bindings() {
+    let refs = variables(&json!({ "let": [
+        "total", { "+": [{ "var": "price" }, { "var": "total" }] },
+        "rate", { "var": "total.rate" },
+        { "map": [{ "var": "items" }, { "*": [{ "var": "total" }, { "var": "rate" }, { "var": "qty" }] }] }
+    ] }))
+    .unwrap();
+
+    let paths: Vec<Option<&str>> = refs.iter().map(|r| r.static_path()).collect();
+    assert_eq!(
+        paths,
+        vec![Some("price"), Some("total"), Some("items"), Some("qty")]
+    );
+}